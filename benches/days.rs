@@ -0,0 +1,84 @@
+//! Benchmarks of every day's parse, part 1 and part 2.
+//!
+//! `cargo bench [-- <days>...] [--time SECS] [--baseline NAME] [--save-baseline NAME]
+//! [--param name=value]... [--config FILE]`
+//!
+//! Every run is compared against the `previous` baseline, which is then replaced by the new
+//! measurements. With `--baseline NAME` the run is compared against a named baseline instead and
+//! `--save-baseline NAME` stores the run under a name, e.g. before starting an optimization.
+
+use std::env;
+use std::time::Duration;
+
+use advent_of_code_template::etc::args::Args;
+use advent_of_code_template::etc::bench::{self, Baseline, Bench};
+use advent_of_code_template::etc::params;
+
+const PREVIOUS: &str = "previous";
+
+fn main() {
+    let args: Vec<String> = env::args().skip(1).collect();
+    let args = Args::parse(
+        &args,
+        &[
+            "--time",
+            "--baseline",
+            "--save-baseline",
+            "--param",
+            "--config",
+        ],
+    );
+    let measure = Duration::from_secs_f64(args.value("--time").unwrap_or(1.0));
+    let days = match args.days() {
+        days if days.is_empty() => (1..=25).collect(),
+        days => days,
+    };
+    params::init(
+        args.value::<String>("--config").as_deref(),
+        &args.values::<String>("--param"),
+        &days,
+    );
+
+    let compare_to = args
+        .value::<String>("--baseline")
+        .unwrap_or(PREVIOUS.to_string());
+    let baseline = Baseline::load(&compare_to);
+
+    let mut measurements = Vec::new();
+    for &day in &days {
+        let Some(hook) = bench::get_day_bench(day) else {
+            continue;
+        };
+
+        println!("\n=== Day {:02} ===", day);
+        let mut bench = Bench::new(day, measure);
+        hook(&mut bench);
+
+        let width = bench.measurements.iter().map(|m| m.group.len()).max();
+        for m in &bench.measurements {
+            println!(
+                "  · {:<width$} {:>10}  (± {:>10}, min {:>10}, {} runs)  {}",
+                m.group,
+                bench::format_ns(m.median_ns),
+                bench::format_ns(m.stddev_ns),
+                bench::format_ns(m.min_ns),
+                m.iterations,
+                bench::compare(m, baseline.get(day, &m.group)),
+                width = width.unwrap_or(0).max(9)
+            );
+        }
+        measurements.extend(bench.measurements);
+    }
+
+    let mut save = vec![PREVIOUS.to_string()];
+    save.extend(args.value::<String>("--save-baseline"));
+    if args.value::<String>("--baseline").is_some() {
+        // keep comparing against the same previous run while working against a named baseline
+        save.remove(0);
+    }
+    for name in save {
+        if let Err(e) = Baseline::save(&name, &measurements) {
+            eprintln!("Could not save baseline {}: {}", name, e);
+        }
+    }
+}
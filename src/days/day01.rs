@@ -1,55 +1,432 @@
-use std::collections::HashMap;
+use std::io;
+use std::str::FromStr;
 
+#[cfg(feature = "parallel")]
+use rayon::iter::{IntoParallelIterator, ParallelIterator};
+
+use crate::etc::bench::Bench;
+use crate::etc::explain::Explanation;
+use crate::etc::params;
+use crate::utils::file::LineReader;
+use crate::utils::hash::FastMap;
+use crate::utils::random::Rng;
 use crate::{read_input, Solution, SolutionPair};
 
 pub fn solve() -> SolutionPair {
-    let (mut left, mut right) = get_input();
+    let params = Params::load();
+    let mut columns = get_input();
+
+    let sol1 = part1(&mut columns);
+    let sol2 = part2(&columns, &params);
+    (to_solution(sol1), to_solution(sol2))
+}
+
+pub fn solve_stream(lines: &mut LineReader) -> io::Result<SolutionPair> {
+    let params = Params::load();
+    let mut columns = read_columns(lines)?;
 
-    let sol1 = part1(&mut left, &mut right);
-    let sol2 = part2(&left, &right);
-    (Solution::from(sol1), Solution::from(sol2))
+    let sol1 = part1(&mut columns);
+    let sol2 = part2(&columns, &params);
+    Ok((to_solution(sol1), to_solution(sol2)))
 }
 
-fn part1(left: &mut [u32], right: &mut [u32]) -> u32 {
-    left.sort();
-    right.sort();
+pub fn bench(bench: &mut Bench) {
+    let params = Params::load();
+    bench.run("parse", get_input);
+    let mut columns = get_input();
+    bench.run_with(
+        "part1",
+        || columns.clone(),
+        |mut columns| part1(&mut columns),
+    );
+    sort_columns(&mut columns);
+    bench.run("part2", || part2(&columns, &params));
 
-    let mut result = 0u32;
-    for i in 0..left.len() {
-        result += left[i].abs_diff(right[i]);
+    // similarity strategies on random sorted lists of growing length, to find the crossover
+    let mut rng = Rng::new(1);
+    for size in [1_000, 10_000, 100_000, 1_000_000] {
+        let mut columns: Vec<Vec<u64>> = (0..2)
+            .map(|_| (0..size).map(|_| rng.range(10000, 99999)).collect())
+            .collect();
+        sort_columns(&mut columns);
+        let (left, right) = (&columns[0], &columns[1]);
+
+        bench.run(&format!("hash/{}", size), || {
+            similarity(left, &count_values(right))
+        });
+        bench.run(&format!("merge/{}", size), || similarity_merge(left, right));
+        bench.run(&format!("parallel/{}", size), || {
+            similarity_parallel(left, right)
+        });
     }
+}
 
-    result
+/// Params
+///
+/// How part 2 computes the similarity score: `hash` counts the right list in a map, `merge`
+/// walks both sorted lists and `parallel` merges chunks of them on all threads.
+struct Params {
+    similarity: Similarity,
 }
 
-fn part2(left: &[u32], right: &[u32]) -> u32 {
-    let mut right_map = HashMap::new();
-    for value in right {
-        match right_map.get_mut(value) {
-            Some(count) => *count += 1,
-            None => {
-                right_map.insert(*value, 1);
-            }
+impl Params {
+    fn load() -> Self {
+        Self {
+            similarity: params::get(1, "similarity", Similarity::Merge),
         }
     }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Similarity {
+    Hash,
+    Merge,
+    Parallel,
+}
+
+impl FromStr for Similarity {
+    type Err = String;
+
+    fn from_str(similarity: &str) -> Result<Self, Self::Err> {
+        match similarity {
+            "hash" => Ok(Self::Hash),
+            "merge" => Ok(Self::Merge),
+            "parallel" => Ok(Self::Parallel),
+            _ => Err(format!("Unsupported similarity: {}", similarity)),
+        }
+    }
+}
+
+/// Distance between the first two columns.
+fn part1(columns: &mut [Vec<u64>]) -> u128 {
+    sort_columns(columns);
+    distance(&columns[0], &columns[1])
+}
+
+/// Similarity score of the first two columns, which `part1` has sorted.
+fn part2(columns: &[Vec<u64>], params: &Params) -> u128 {
+    let (left, right) = (&columns[0], &columns[1]);
+    match params.similarity {
+        Similarity::Hash => similarity(left, &count_values(right)),
+        Similarity::Merge => similarity_merge(left, right),
+        Similarity::Parallel => similarity_parallel(left, right),
+    }
+}
+
+fn sort_columns(columns: &mut [Vec<u64>]) {
+    for column in columns {
+        column.sort_unstable();
+    }
+}
+
+/// Sum of the differences between the i-th smallest values of two sorted columns.
+fn distance(left: &[u64], right: &[u64]) -> u128 {
+    left.iter()
+        .zip(right)
+        .map(|(l, r)| l.abs_diff(*r) as u128)
+        .sum()
+}
+
+fn count_values(column: &[u64]) -> FastMap<u64, u64> {
+    let mut counts = FastMap::default();
+    for value in column {
+        *counts.entry(*value).or_insert(0) += 1;
+    }
+
+    counts
+}
+
+/// Sum of every value of `left` times the number of times it appears in the counted column.
+fn similarity(left: &[u64], right_counts: &FastMap<u64, u64>) -> u128 {
+    left.iter()
+        .map(|value| *value as u128 * *right_counts.get(value).unwrap_or(&0) as u128)
+        .sum()
+}
 
-    let mut result: u32 = 0;
-    for value in left {
-        let count = right_map.get(value).map(|x| *x).unwrap_or(0);
-        result += *value * count;
+/// Similarity score of two sorted columns, walking both at once. Every run of equal values
+/// adds `value * run_left * run_right`.
+fn similarity_merge(left: &[u64], right: &[u64]) -> u128 {
+    let (mut i, mut j) = (0, 0);
+    let mut result = 0;
+    while i < left.len() && j < right.len() {
+        if left[i] < right[j] {
+            i += 1;
+        } else if left[i] > right[j] {
+            j += 1;
+        } else {
+            let value = left[i];
+            let i_start = i;
+            while i < left.len() && left[i] == value {
+                i += 1;
+            }
+            let j_start = j;
+            while j < right.len() && right[j] == value {
+                j += 1;
+            }
+            result += value as u128 * ((i - i_start) * (j - j_start)) as u128;
+        }
     }
 
     result
 }
 
-fn get_input() -> (Vec<u32>, Vec<u32>) {
-    read_input!(01)
-        .split("\n")
-        .filter(|line| !line.is_empty())
-        .map(|line| {
-            let mut split = line.split_ascii_whitespace();
-            (split.next().unwrap(), split.next().unwrap())
+/// Merge similarity on chunks of the left column, each against the part of the right column
+/// within its value range. Runs split between chunks still add up, as every left value is
+/// counted against all of its matches.
+fn similarity_parallel(left: &[u64], right: &[u64]) -> u128 {
+    const CHUNK: usize = 1 << 14;
+
+    let chunks: Vec<&[u64]> = left.chunks(CHUNK).collect();
+    #[cfg(feature = "parallel")]
+    let chunks = chunks.into_par_iter();
+    #[cfg(not(feature = "parallel"))]
+    let chunks = chunks.into_iter();
+
+    chunks
+        .map(|chunk| {
+            let from = right.partition_point(|v| *v < chunk[0]);
+            let to = right.partition_point(|v| *v <= chunk[chunk.len() - 1]);
+            similarity_merge(chunk, &right[from..to])
         })
-        .map(|(left, right)| (left.parse::<u32>().unwrap(), right.parse::<u32>().unwrap()))
-        .unzip()
+        .sum()
+}
+
+/// Distances between all pairs of sorted columns, the matrix is symmetric.
+fn distance_matrix(columns: &[Vec<u64>]) -> Vec<Vec<u128>> {
+    let n = columns.len();
+    let mut matrix = vec![vec![0; n]; n];
+    for i in 0..n {
+        for j in i + 1..n {
+            matrix[i][j] = distance(&columns[i], &columns[j]);
+            matrix[j][i] = matrix[i][j];
+        }
+    }
+
+    matrix
+}
+
+/// Similarity scores between all pairs of columns. Every shared value counts `value * count_a *
+/// count_b`, so the matrix is symmetric as well.
+fn similarity_matrix(columns: &[Vec<u64>]) -> Vec<Vec<u128>> {
+    let counts: Vec<FastMap<u64, u64>> = columns.iter().map(|c| count_values(c)).collect();
+    let n = columns.len();
+    let mut matrix = vec![vec![0; n]; n];
+    for i in 0..n {
+        for j in i + 1..n {
+            matrix[i][j] = similarity(&columns[i], &counts[j]);
+            matrix[j][i] = matrix[i][j];
+        }
+    }
+
+    matrix
+}
+
+/// Answers that fit are reported as `u64`, only huge inputs need all 128 bits.
+fn to_solution(value: u128) -> Solution {
+    match u64::try_from(value) {
+        Ok(value) => Solution::from(value),
+        Err(_) => Solution::from(value),
+    }
+}
+
+/// Distance and similarity matrices of all columns, and the most and least similar pairs.
+pub fn explain() -> Explanation {
+    let mut columns = get_input();
+    sort_columns(&mut columns);
+    let distances = distance_matrix(&columns);
+    let similarities = similarity_matrix(&columns);
+    let mut explanation = Explanation::default();
+
+    let n = columns.len();
+    let pairs: Vec<(usize, usize)> = (0..n)
+        .flat_map(|i| (i + 1..n).map(move |j| (i, j)))
+        .collect();
+
+    explanation.section(format!("Distances of {} columns", n));
+    for &(i, j) in &pairs {
+        explanation.entry(format!("{} - {}", i + 1, j + 1), distances[i][j]);
+    }
+
+    explanation.section("Similarity scores");
+    for &(i, j) in &pairs {
+        explanation.entry(format!("{} - {}", i + 1, j + 1), similarities[i][j]);
+    }
+
+    explanation.section("Ranking by similarity score");
+    if let Some((most, least)) = rank_pairs(&pairs, &similarities) {
+        for (label, (i, j)) in [("most similar", most), ("least similar", least)] {
+            explanation.entry(
+                label,
+                format!(
+                    "{} - {} (score {}, distance {})",
+                    i + 1,
+                    j + 1,
+                    similarities[i][j],
+                    distances[i][j]
+                ),
+            );
+        }
+    }
+
+    explanation
+}
+
+/// Most and least similar of the pairs, none if there are no pairs.
+fn rank_pairs(
+    pairs: &[(usize, usize)],
+    similarities: &[Vec<u128>],
+) -> Option<((usize, usize), (usize, usize))> {
+    let most = pairs.iter().max_by_key(|(i, j)| similarities[*i][*j])?;
+    let least = pairs.iter().min_by_key(|(i, j)| similarities[*i][*j])?;
+    Some((*most, *least))
+}
+
+fn get_input() -> Vec<Vec<u64>> {
+    read_columns(&mut LineReader::new(read_input!(01).as_bytes())).unwrap()
+}
+
+/// Reads any number of whitespace-separated columns, every line has to have the same number of
+/// values. An empty input still compares two empty lists.
+fn read_columns(lines: &mut LineReader) -> io::Result<Vec<Vec<u64>>> {
+    let mut columns: Vec<Vec<u64>> = Vec::new();
+    let mut row = 0;
+    lines.for_each(|line| {
+        row += 1;
+        let values: Vec<u64> = line
+            .split_ascii_whitespace()
+            .map(|value| value.parse::<u64>().unwrap())
+            .collect();
+
+        if columns.is_empty() {
+            columns = vec![Vec::new(); values.len()];
+        } else if values.len() != columns.len() {
+            panic!(
+                "Line {} has {} columns, expected {}",
+                row,
+                values.len(),
+                columns.len()
+            );
+        }
+
+        for (column, value) in columns.iter_mut().zip(values) {
+            column.push(value);
+        }
+    })?;
+
+    if columns.is_empty() {
+        columns = vec![Vec::new(); 2];
+    } else if columns.len() < 2 {
+        panic!("Day 01 compares at least two columns, found 1");
+    }
+
+    Ok(columns)
+}
+
+/// One line per location ID pair. About a third of the right column repeats values from the
+/// left one, so the similarity score is not trivially zero.
+pub fn generate(size: usize, rng: &mut Rng) -> String {
+    let left: Vec<u64> = (0..size).map(|_| rng.range(10000, 99999)).collect();
+    let mut input = String::with_capacity(size * 14);
+    for value in &left {
+        let right = if rng.chance(1, 3) {
+            left[rng.index(size)]
+        } else {
+            rng.range(10000, 99999)
+        };
+        input.push_str(&format!("{}   {}\n", value, right));
+    }
+
+    input
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const EXAMPLE: &str = "3 4 1\n4 3 2\n2 5 3\n1 3 4\n3 9 5\n3 3 6\n";
+
+    fn read_example(input: &str) -> Vec<Vec<u64>> {
+        read_columns(&mut LineReader::new(input.as_bytes())).unwrap()
+    }
+
+    #[test]
+    fn matrix_test() {
+        let mut columns = read_example(EXAMPLE);
+        assert_eq!(3, columns.len());
+        sort_columns(&mut columns);
+
+        let distances = distance_matrix(&columns);
+        assert_eq!(
+            vec![vec![0, 11, 5], vec![11, 0, 6], vec![5, 6, 0]],
+            distances
+        );
+        let similarities = similarity_matrix(&columns);
+        assert_eq!(
+            vec![vec![0, 31, 16], vec![31, 0, 18], vec![16, 18, 0]],
+            similarities
+        );
+
+        let pairs = [(0, 1), (0, 2), (1, 2)];
+        assert_eq!(Some(((0, 1), (0, 2))), rank_pairs(&pairs, &similarities));
+        assert_eq!(None, rank_pairs(&[], &similarities));
+    }
+
+    #[test]
+    fn read_columns_test() {
+        assert_eq!(vec![Vec::<u64>::new(); 2], read_example(""));
+        assert_eq!(vec![vec![1, 3], vec![2, 4]], read_example("1 2\n3   4\n"));
+    }
+
+    #[test]
+    #[should_panic(expected = "Line 2 has 1 columns, expected 2")]
+    fn read_columns_ragged_test() {
+        read_example("1 2\n3\n");
+    }
+
+    #[test]
+    fn similarity_test() {
+        fn assert_agree(left: &[u64], right: &[u64], expected: u128) {
+            assert_eq!(expected, similarity(left, &count_values(right)));
+            assert_eq!(expected, similarity_merge(left, right));
+            assert_eq!(expected, similarity_parallel(left, right));
+        }
+
+        assert_agree(&[], &[], 0);
+        assert_agree(&[1, 2, 3], &[], 0);
+        assert_agree(&[], &[1, 2, 3], 0);
+
+        // runs longer than a chunk of the parallel merge, straddling the chunk boundaries
+        let run = (1 << 14) + 100;
+        let left: Vec<u64> = [(3, 50), (5, run), (8, run), (9, 1)]
+            .iter()
+            .flat_map(|&(value, count)| std::iter::repeat_n(value, count))
+            .collect();
+        let right: Vec<u64> = [(2, 7), (5, 3), (8, run), (10, 4)]
+            .iter()
+            .flat_map(|&(value, count)| std::iter::repeat_n(value, count))
+            .collect();
+        let expected = (5 * run * 3 + 8 * run * run) as u128;
+        assert_agree(&left, &right, expected);
+        assert_agree(&right, &left, expected);
+
+        let mut rng = Rng::new(1);
+        let mut columns: Vec<Vec<u64>> = (0..2)
+            .map(|_| (0..50_000).map(|_| rng.range(1, 100)).collect())
+            .collect();
+        sort_columns(&mut columns);
+        let expected = similarity_merge(&columns[0], &columns[1]);
+        assert_agree(&columns[0], &columns[1], expected);
+    }
+
+    #[test]
+    fn to_solution_test() {
+        assert!(matches!(
+            to_solution(u64::MAX as u128),
+            Solution::U64(u64::MAX)
+        ));
+        assert!(matches!(
+            to_solution(u64::MAX as u128 + 1),
+            Solution::U128(value) if value == u64::MAX as u128 + 1
+        ));
+    }
 }
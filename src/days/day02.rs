@@ -1,28 +1,230 @@
+use std::io;
+
+use crate::etc::bench::Bench;
+use crate::etc::explain::Explanation;
+use crate::etc::params;
+use crate::utils::file::LineReader;
+use crate::utils::random::Rng;
 use crate::{read_input, Solution, SolutionPair};
 
 pub fn solve() -> SolutionPair {
+    let params = Params::load();
     let levels = get_input();
 
-    let sol1 = part1(&levels);
-    let sol2 = part2(&levels);
+    let sol1 = part1(&levels, &params);
+    let sol2 = part2(&levels, &params);
 
     (Solution::from(sol1), Solution::from(sol2))
 }
 
-fn part1(levels: &Vec<Vec<u32>>) -> usize {
+/// Checks every report as it is read, without keeping any of them.
+pub fn solve_stream(lines: &mut LineReader) -> io::Result<SolutionPair> {
+    let params = Params::load();
+    let Params {
+        tolerance,
+        min_step,
+        max_step,
+    } = params;
+    let (mut sol1, mut sol2) = (0usize, 0usize);
+    lines.for_each(|line| {
+        let level = parse_level(line);
+        sol1 += is_report_safe(&level, 0, min_step, max_step) as usize;
+        sol2 += is_report_safe(&level, tolerance, min_step, max_step) as usize;
+    })?;
+
+    Ok((Solution::from(sol1), Solution::from(sol2)))
+}
+
+pub fn bench(bench: &mut Bench) {
+    let params = Params::load();
+    bench.run("parse", get_input);
+    let levels = get_input();
+    bench.run("part1", || part1(&levels, &params));
+    bench.run("part2", || part2(&levels, &params));
+    bench.run("part2-fast", || {
+        levels
+            .iter()
+            .filter(|level| is_level_safe_2_fast(level))
+            .count()
+    });
+}
+
+/// Params
+///
+/// Number of levels part 2 may remove from a report, and the allowed steps between adjacent
+/// levels.
+#[derive(Clone, Copy)]
+struct Params {
+    tolerance: usize,
+    min_step: u32,
+    max_step: u32,
+}
+
+impl Params {
+    fn load() -> Self {
+        Self {
+            tolerance: params::get(2, "tolerance", 1),
+            min_step: params::get(2, "min_step", 1),
+            max_step: params::get(2, "max_step", 3),
+        }
+    }
+}
+
+fn part1(levels: &[Vec<u32>], params: &Params) -> usize {
     levels
         .iter()
-        .filter(|level| is_level_safe_1(*level))
+        .filter(|level| is_report_safe(level, 0, params.min_step, params.max_step))
         .count()
 }
 
-fn part2(levels: &Vec<Vec<u32>>) -> usize {
+fn part2(levels: &[Vec<u32>], params: &Params) -> usize {
     levels
         .iter()
-        .filter(|level| is_level_safe_2_fast(*level))
+        .filter(|level| is_report_safe(level, params.tolerance, params.min_step, params.max_step))
         .count()
 }
 
+/// Is Report Safe
+///
+/// Whether removing at most `k` levels leaves a report that is strictly increasing or
+/// decreasing with every step between `min_step` and `max_step`.
+///
+/// For both directions, `longest[i]` is the length of the longest safe subsequence ending at
+/// level `i`. Skipping more than `k` levels in between is never allowed, so only the `k + 1`
+/// levels before `i` can precede it. The report is safe if a subsequence keeps `len - k` levels.
+pub fn is_report_safe(level: &[u32], k: usize, min_step: u32, max_step: u32) -> bool {
+    if level.len() <= k + 1 {
+        return true;
+    }
+
+    // reports are short, only unusually long ones need a heap buffer
+    let mut stack = [0; 16];
+    let mut heap = Vec::new();
+    let longest: &mut [usize] = if level.len() <= stack.len() {
+        &mut stack[..level.len()]
+    } else {
+        heap.resize(level.len(), 0);
+        &mut heap
+    };
+
+    let keep = level.len() - k;
+    for increasing in [true, false] {
+        for i in 0..level.len() {
+            longest[i] = 1;
+            for j in i.saturating_sub(k + 1)..i {
+                let step = if increasing {
+                    level[i].checked_sub(level[j])
+                } else {
+                    level[j].checked_sub(level[i])
+                };
+                if step.is_some_and(|step| min_step <= step && step <= max_step) {
+                    longest[i] = longest[i].max(longest[j] + 1);
+                }
+            }
+
+            if longest[i] >= keep {
+                return true;
+            }
+        }
+    }
+
+    false
+}
+
+/// Kind of the first step of a report that breaks the rules.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Violation {
+    DirectionChange,
+    StepTooSmall,
+    StepTooLarge,
+}
+
+/// Diagnosis
+///
+/// Why a report is unsafe: the first level that breaks the rules, how it breaks them and the
+/// levels whose removal alone would make the report safe.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Diagnosis {
+    pub index: usize,
+    pub violation: Violation,
+    pub removable: Vec<usize>,
+}
+
+/// Diagnoses the report, `None` if it is safe as it is. The direction is set by the first step.
+pub fn diagnose_report(level: &[u32], min_step: u32, max_step: u32) -> Option<Diagnosis> {
+    let increasing = level.len() > 1 && level[1] >= level[0];
+    let (index, violation) = (1..level.len()).find_map(|i| {
+        let (prev, cur) = (level[i - 1], level[i]);
+        let violation = if cur != prev && (cur > prev) != increasing {
+            Violation::DirectionChange
+        } else if cur.abs_diff(prev) < min_step {
+            Violation::StepTooSmall
+        } else if cur.abs_diff(prev) > max_step {
+            Violation::StepTooLarge
+        } else {
+            return None;
+        };
+        Some((i, violation))
+    })?;
+
+    let removable = (0..level.len())
+        .filter(|&i| {
+            let sub_level = [&level[..i], &level[i + 1..]].concat();
+            is_report_safe(&sub_level, 0, min_step, max_step)
+        })
+        .collect();
+
+    Some(Diagnosis {
+        index,
+        violation,
+        removable,
+    })
+}
+
+/// Every unsafe report with its first violation and the levels that could be removed.
+pub fn explain() -> Explanation {
+    let params = Params::load();
+    let levels = get_input();
+    let mut explanation = Explanation::default();
+
+    explanation.section("Unsafe reports");
+    for (i, level) in levels.iter().enumerate() {
+        let Some(diagnosis) = diagnose_report(level, params.min_step, params.max_step) else {
+            continue;
+        };
+
+        let (prev, cur) = (level[diagnosis.index - 1], level[diagnosis.index]);
+        let violation = match diagnosis.violation {
+            Violation::DirectionChange => "direction changes",
+            Violation::StepTooSmall => "step too small",
+            Violation::StepTooLarge => "step too large",
+        };
+        let removable = if diagnosis.removable.is_empty() {
+            String::from("no single level")
+        } else {
+            let indices: Vec<String> = diagnosis.removable.iter().map(|i| i.to_string()).collect();
+            format!("index {}", indices.join(" or "))
+        };
+        let report: Vec<String> = level.iter().map(|v| v.to_string()).collect();
+
+        explanation.entry(
+            format!("report {}", i + 1),
+            format!(
+                "{}: {} at index {} ({} -> {}), remove {}",
+                report.join(" "),
+                violation,
+                diagnosis.index,
+                prev,
+                cur,
+                removable
+            ),
+        );
+    }
+
+    explanation
+}
+
+#[cfg(test)]
 fn is_level_safe_1(level: &[u32]) -> bool {
     let factor = if level[1] < level[0] { -1 } else { 1 };
     for i in 1..level.len() {
@@ -35,7 +237,7 @@ fn is_level_safe_1(level: &[u32]) -> bool {
     true
 }
 
-#[allow(dead_code)]
+#[cfg(test)]
 fn is_level_safe_2_simple(level: &[u32]) -> bool {
     for i in 0..level.len() {
         let sub_level = [&level[..i], &level[i + 1..]].concat();
@@ -110,13 +312,102 @@ fn is_level_safe_2_factor(level: &[u32], factor: i32) -> Option<usize> {
 }
 
 fn get_input() -> Vec<Vec<u32>> {
-    read_input!(02)
-        .split("\n")
-        .filter(|line| !line.is_empty())
-        .map(|line| {
-            line.split_ascii_whitespace()
-                .map(|c| c.parse::<u32>().unwrap())
-                .collect()
-        })
+    let mut levels = Vec::new();
+    LineReader::new(read_input!(02).as_bytes())
+        .for_each(|line| levels.push(parse_level(line)))
+        .unwrap();
+
+    levels
+}
+
+fn parse_level(line: &str) -> Vec<u32> {
+    line.split_ascii_whitespace()
+        .map(|c| c.parse::<u32>().unwrap())
         .collect()
 }
+
+/// One report per line with 5 to 8 levels. Reports are mostly monotone with steps of 1 to 3,
+/// and some of them get a single corrupted level.
+pub fn generate(size: usize, rng: &mut Rng) -> String {
+    let mut input = String::new();
+    for _ in 0..size {
+        let len = rng.range(5, 8) as usize;
+        let increasing = rng.chance(1, 2);
+        let mut level: Vec<i64> = Vec::with_capacity(len);
+        // decreasing reports start high enough that every level stays positive
+        let lowest = if increasing {
+            10
+        } else {
+            3 * (len as u64 - 1) + 1
+        };
+        level.push(rng.range(lowest, 90) as i64);
+        for i in 1..len {
+            let step = rng.range(1, 3) as i64;
+            level.push(if increasing {
+                level[i - 1] + step
+            } else {
+                level[i - 1] - step
+            });
+        }
+
+        if rng.chance(1, 2) {
+            let i = rng.index(len);
+            level[i] = rng.range(1, 99) as i64;
+        }
+
+        let line: Vec<String> = level.iter().map(|v| v.to_string()).collect();
+        input.push_str(&line.join(" "));
+        input.push('\n');
+    }
+
+    input
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn is_report_safe_test() {
+        let reports = [
+            vec![7, 6, 4, 2, 1],
+            vec![1, 2, 7, 8, 9],
+            vec![9, 7, 6, 2, 1],
+            vec![1, 3, 2, 4, 5],
+            vec![8, 6, 4, 4, 1],
+            vec![1, 3, 6, 7, 9],
+            vec![5, 1, 2, 3, 4],
+            vec![1, 2, 3, 4, 9],
+            vec![3, 1, 2, 3, 4, 3],
+        ];
+        for report in &reports {
+            assert_eq!(is_level_safe_1(report), is_report_safe(report, 0, 1, 3));
+            assert_eq!(
+                is_level_safe_2_simple(report),
+                is_report_safe(report, 1, 1, 3)
+            );
+        }
+
+        assert!(!is_report_safe(&[3, 1, 2, 3, 4, 3], 1, 1, 3));
+        assert!(is_report_safe(&[3, 1, 2, 3, 4, 3], 2, 1, 3));
+        assert!(is_report_safe(&[1, 2, 7, 8, 9], 0, 1, 5));
+    }
+
+    #[test]
+    fn diagnose_report_test() {
+        assert_eq!(None, diagnose_report(&[7, 6, 4, 2, 1], 1, 3));
+
+        let diagnosis = diagnose_report(&[1, 3, 2, 4, 5], 1, 3).unwrap();
+        assert_eq!(2, diagnosis.index);
+        assert_eq!(Violation::DirectionChange, diagnosis.violation);
+        assert_eq!(vec![1, 2], diagnosis.removable);
+
+        let diagnosis = diagnose_report(&[8, 6, 4, 4, 1], 1, 3).unwrap();
+        assert_eq!(Violation::StepTooSmall, diagnosis.violation);
+        assert_eq!(vec![2, 3], diagnosis.removable);
+
+        let diagnosis = diagnose_report(&[1, 2, 7, 8, 9], 1, 3).unwrap();
+        assert_eq!(Violation::StepTooLarge, diagnosis.violation);
+        assert!(diagnosis.removable.is_empty());
+    }
+}
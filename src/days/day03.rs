@@ -1,47 +1,190 @@
+use std::fmt::{Display, Formatter};
+use std::io;
+use std::mem;
+use std::ops::{Range, RangeInclusive};
+
+#[cfg(feature = "regex")]
 use regex::Regex;
 
+use crate::etc::bench::Bench;
+use crate::etc::explain::Explanation;
+use crate::etc::params;
+use crate::utils::file::LineReader;
 use crate::{read_input, Solution, SolutionPair};
 
 pub fn solve() -> SolutionPair {
-    let input = parse_input();
+    let set = Params::load().instruction_set();
+    let input = parse_input(&set);
 
-    let sol1: u32 = part1(&input);
-    let sol2: u32 = part2(&input);
+    let sol1: i128 = part1(&input);
+    let sol2: i128 = part2(&input);
 
     (Solution::from(sol1), Solution::from(sol2))
 }
 
-enum Instruction {
-    Mul(u32, u32),
-    Do,
-    Dont,
+/// Tokenizes the memory chunk by chunk as it is read.
+pub fn solve_stream(lines: &mut LineReader) -> io::Result<SolutionPair> {
+    let set = Params::load().instruction_set();
+    let mut tokenizer = Tokenizer::new(&set);
+    let mut input = Vec::new();
+    lines.for_each_chunk(|chunk| tokenizer.feed(chunk, |token| input.push(token.instruction)))?;
+
+    let sol1: i128 = part1(&input);
+    let sol2: i128 = part2(&input);
+
+    Ok((Solution::from(sol1), Solution::from(sol2)))
+}
+
+pub fn bench(bench: &mut Bench) {
+    let set = Params::load().instruction_set();
+    bench.run("parse", || parse_input(&set));
+    #[cfg(feature = "regex")]
+    bench.run("parse-regex", parse_input_regex);
+    let input = parse_input(&set);
+    bench.run("part1", || part1(&input));
+    bench.run("part2", || part2(&input));
+}
+
+struct Params {
+    instructions: String,
+    min_digits: usize,
+    max_digits: usize,
+}
+
+impl Params {
+    fn load() -> Self {
+        Self {
+            instructions: params::get(3, "instructions", String::from("mul,do,don't")),
+            min_digits: params::get(3, "min_digits", 1),
+            max_digits: params::get(3, "max_digits", 3),
+        }
+    }
+
+    fn names(&self) -> Vec<&str> {
+        self.instructions.split(',').map(str::trim).collect()
+    }
+
+    fn instruction_set(&self) -> InstructionSet {
+        InstructionSet::new(&self.names(), self.min_digits..=self.max_digits)
+    }
+}
+
+/// What executing an instruction does.
+#[derive(Clone, Copy, Debug)]
+pub enum Effect {
+    /// Adds the value computed from the operands to the result.
+    Value(fn(&[u64]) -> i128),
+    Enable,
+    Disable,
 }
 
-fn part1(input: &[Instruction]) -> u32 {
+/// Instruction name, number of operands and effect.
+#[derive(Debug)]
+pub struct Definition {
+    pub name: &'static str,
+    pub arity: usize,
+    pub effect: Effect,
+}
+
+pub const MAX_ARITY: usize = 4;
+
+/// Every instruction the interpreter knows, the `instructions` parameter picks the ones that
+/// are recognized in the memory. A new instruction only needs a line here.
+pub const INSTRUCTIONS: &[Definition] = &[
+    Definition {
+        name: "mul",
+        arity: 2,
+        effect: Effect::Value(|args| args[0] as i128 * args[1] as i128),
+    },
+    Definition {
+        name: "add",
+        arity: 2,
+        effect: Effect::Value(|args| args[0] as i128 + args[1] as i128),
+    },
+    Definition {
+        name: "neg",
+        arity: 1,
+        effect: Effect::Value(|args| -(args[0] as i128)),
+    },
+    Definition {
+        name: "do",
+        arity: 0,
+        effect: Effect::Enable,
+    },
+    Definition {
+        name: "don't",
+        arity: 0,
+        effect: Effect::Disable,
+    },
+];
+
+fn definition_id(name: &str) -> Option<usize> {
+    INSTRUCTIONS.iter().position(|d| d.name == name)
+}
+
+/// Instruction of the `INSTRUCTIONS` table with its operands.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Instruction {
+    id: usize,
+    args: [u64; MAX_ARITY],
+}
+
+impl Instruction {
+    pub fn new(id: usize, args: &[u64]) -> Self {
+        let mut instruction = Self {
+            id,
+            args: [0; MAX_ARITY],
+        };
+        instruction.args[..args.len()].copy_from_slice(args);
+        instruction
+    }
+
+    pub fn definition(&self) -> &'static Definition {
+        &INSTRUCTIONS[self.id]
+    }
+
+    pub fn args(&self) -> &[u64] {
+        &self.args[..self.definition().arity]
+    }
+
+    /// Value added to the result, `None` for the instructions that only switch it on or off.
+    pub fn value(&self) -> Option<i128> {
+        match self.definition().effect {
+            Effect::Value(f) => Some(f(self.args())),
+            _ => None,
+        }
+    }
+}
+
+impl Display for Instruction {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let args: Vec<String> = self.args().iter().map(|a| a.to_string()).collect();
+        write!(f, "{}({})", self.definition().name, args.join(","))
+    }
+}
+
+fn part1(input: &[Instruction]) -> i128 {
     input
         .iter()
-        .map(|instruction| match instruction {
-            Instruction::Mul(a, b) => (*a) * (*b),
-            _ => 0,
-        })
+        .filter_map(|instruction| instruction.value())
         .sum()
 }
 
-fn part2(input: &[Instruction]) -> u32 {
-    let mut mul_enabled = true;
+fn part2(input: &[Instruction]) -> i128 {
+    let mut enabled = true;
     let mut result = 0;
     for instruction in input {
-        match instruction {
-            Instruction::Mul(a, b) => {
-                if mul_enabled {
-                    result += (*a) * (*b);
+        match instruction.definition().effect {
+            Effect::Value(f) => {
+                if enabled {
+                    result += f(instruction.args());
                 }
             }
-            Instruction::Do => {
-                mul_enabled = true;
+            Effect::Enable => {
+                enabled = true;
             }
-            Instruction::Dont => {
-                mul_enabled = false;
+            Effect::Disable => {
+                enabled = false;
             }
         }
     }
@@ -49,20 +192,451 @@ fn part2(input: &[Instruction]) -> u32 {
     result
 }
 
-fn parse_input() -> Vec<Instruction> {
-    let re = Regex::new(r"(mul\((\d{1,3}),(\d{1,3})\))|((do)(\(\)))|((don't)(\(\)))").unwrap();
-    let input = read_input!(03);
+/// Part of the memory in which the values are either all added or all ignored.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Span {
+    pub enabled: bool,
+    pub range: Range<usize>,
+    /// Number of value instructions in the span and the sum of their values.
+    pub count: usize,
+    pub sum: i128,
+}
+
+/// Splits the memory of `len` bytes where an instruction switches between enabled and disabled,
+/// instructions that keep the current state do not start a new span.
+pub fn trace(tokens: &[Token], len: usize) -> Vec<Span> {
+    let mut spans = vec![Span {
+        enabled: true,
+        range: 0..len,
+        count: 0,
+        sum: 0,
+    }];
+
+    for token in tokens {
+        let span = spans.last_mut().unwrap();
+        let enabled = match token.instruction.definition().effect {
+            Effect::Value(f) => {
+                span.count += 1;
+                span.sum += f(token.instruction.args());
+                continue;
+            }
+            Effect::Enable => true,
+            Effect::Disable => false,
+        };
+        if enabled != span.enabled {
+            span.range.end = token.span.start;
+            spans.push(Span {
+                enabled,
+                range: token.span.start..len,
+                count: 0,
+                sum: 0,
+            });
+        }
+    }
+
+    spans
+}
+
+/// Instruction found in the memory together with the bytes it spans.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Token {
+    pub instruction: Instruction,
+    pub span: Range<usize>,
+}
+
+#[derive(Clone, Debug, Default)]
+struct Node {
+    next: Vec<(u8, usize)>,
+    id: Option<usize>,
+}
+
+/// Instruction Set
+///
+/// The instructions recognized in the memory, as a trie of their names, and how many digits
+/// their operands may have. Operands that do not fit into a `u64` are never matched.
+#[derive(Clone, Debug)]
+pub struct InstructionSet {
+    trie: Vec<Node>,
+    digits: RangeInclusive<usize>,
+    // a byte after the first of some name can start another one, failed matches look back
+    look_back: bool,
+}
+
+impl InstructionSet {
+    pub fn new(names: &[&str], digits: RangeInclusive<usize>) -> Self {
+        if *digits.start() == 0 || digits.is_empty() {
+            panic!("Operands need at least one digit, not {:?}", digits);
+        }
+
+        let mut trie = vec![Node::default()];
+        for name in names {
+            let id = definition_id(name).unwrap_or_else(|| {
+                let known: Vec<&str> = INSTRUCTIONS.iter().map(|d| d.name).collect();
+                panic!(
+                    "Unknown instruction `{}`, known are {}",
+                    name,
+                    known.join(", ")
+                )
+            });
+            if INSTRUCTIONS[id].arity > MAX_ARITY {
+                panic!("Instructions have at most {} operands", MAX_ARITY);
+            }
 
-    re.captures_iter(input)
-        .map(|c| c.extract())
-        .map(|(_, [full, a, b])| {
-            if full == "do()" {
-                Instruction::Do
-            } else if full == "don't()" {
-                Instruction::Dont
-            } else {
-                Instruction::Mul(a.parse::<u32>().unwrap(), b.parse::<u32>().unwrap())
+            let mut node = 0;
+            for &c in name.as_bytes() {
+                node = match trie[node].next.iter().find(|(b, _)| *b == c) {
+                    Some(&(_, next)) => next,
+                    None => {
+                        trie.push(Node::default());
+                        let next = trie.len() - 1;
+                        trie[node].next.push((c, next));
+                        next
+                    }
+                };
             }
+            trie[node].id = Some(id);
+        }
+
+        let first: Vec<u8> = trie[0].next.iter().map(|(c, _)| *c).collect();
+        let look_back = names
+            .iter()
+            .any(|name| name.bytes().skip(1).any(|c| first.contains(&c)));
+
+        Self {
+            trie,
+            digits,
+            look_back,
+        }
+    }
+
+    fn next(&self, node: usize, c: u8) -> Option<usize> {
+        self.trie[node]
+            .next
+            .iter()
+            .find(|(b, _)| *b == c)
+            .map(|(_, next)| *next)
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default)]
+enum State {
+    #[default]
+    Idle,
+    /// Inside the name of an instruction, at `node` of the trie.
+    Name { node: usize },
+    /// Digits of operand `index` of the instruction.
+    Operand {
+        id: usize,
+        index: usize,
+        value: u64,
+        digits: usize,
+    },
+}
+
+/// Tokenizer
+///
+/// Byte-level state machine finding the instructions of an [`InstructionSet`], the same as a
+/// leftmost-first regex would. The memory can be fed in chunks of any size, an instruction split
+/// between two chunks is still found.
+///
+/// When no name contains a byte that starts another one, as for `mul`, `do` and `don't`, a
+/// failed match never has to look back: only the byte it failed on can start a new one.
+/// Otherwise the bytes of the current match are kept to scan them again.
+pub struct Tokenizer<'a> {
+    set: &'a InstructionSet,
+    state: State,
+    start: usize,
+    offset: usize,
+    args: [u64; MAX_ARITY],
+    pending: Vec<u8>,
+}
+
+impl<'a> Tokenizer<'a> {
+    pub fn new(set: &'a InstructionSet) -> Self {
+        Self {
+            set,
+            state: State::Idle,
+            start: 0,
+            offset: 0,
+            args: [0; MAX_ARITY],
+            pending: Vec::new(),
+        }
+    }
+
+    /// Feeds the next chunk of memory, calling `emit` with every completed instruction.
+    pub fn feed(&mut self, chunk: &[u8], mut emit: impl FnMut(Token)) {
+        let first = &self.set.trie[0].next;
+        let mut i = 0;
+        while i < chunk.len() {
+            if let State::Idle = self.state {
+                // skip the noise up to the next byte that can start an instruction
+                let skip = chunk[i..]
+                    .iter()
+                    .position(|c| first.iter().any(|(b, _)| b == c))
+                    .unwrap_or(chunk.len() - i);
+                i += skip;
+                self.offset += skip;
+                if i == chunk.len() {
+                    return;
+                }
+            }
+
+            self.push(chunk[i], &mut emit);
+            i += 1;
+        }
+    }
+
+    fn push(&mut self, c: u8, emit: &mut impl FnMut(Token)) {
+        match self.advance(c, emit) {
+            Some(state) => {
+                self.state = state;
+                self.offset += 1;
+                if self.set.look_back {
+                    match state {
+                        State::Idle => self.pending.clear(),
+                        _ => self.pending.push(c),
+                    }
+                }
+            }
+            None if self.set.look_back => {
+                // scan again from the byte after the start of the failed match
+                let mut replay = mem::take(&mut self.pending);
+                replay.push(c);
+                self.state = State::Idle;
+                self.offset = self.start + 1;
+                for &c in &replay[1..] {
+                    self.push(c, emit);
+                }
+            }
+            // from idle every byte is accepted
+            None => {
+                self.state = State::Idle;
+                self.push(c, emit);
+            }
+        }
+    }
+
+    /// State after byte `c`, `None` if the current instruction cannot be completed.
+    fn advance(&mut self, c: u8, emit: &mut impl FnMut(Token)) -> Option<State> {
+        let state = match self.state {
+            State::Idle => {
+                self.start = self.offset;
+                match self.set.next(0, c) {
+                    Some(node) => State::Name { node },
+                    None => State::Idle,
+                }
+            }
+            State::Name { node } if c == b'(' => State::Operand {
+                id: self.set.trie[node].id?,
+                index: 0,
+                value: 0,
+                digits: 0,
+            },
+            State::Name { node } => State::Name {
+                node: self.set.next(node, c)?,
+            },
+            State::Operand {
+                id,
+                index,
+                value,
+                digits,
+            } => {
+                let arity = INSTRUCTIONS[id].arity;
+                let complete = self.set.digits.contains(&digits);
+                match c {
+                    b'0'..=b'9' if index < arity && digits < *self.set.digits.end() => {
+                        State::Operand {
+                            id,
+                            index,
+                            value: value.checked_mul(10)?.checked_add((c - b'0') as u64)?,
+                            digits: digits + 1,
+                        }
+                    }
+                    b',' if complete && index + 1 < arity => {
+                        self.args[index] = value;
+                        State::Operand {
+                            id,
+                            index: index + 1,
+                            value: 0,
+                            digits: 0,
+                        }
+                    }
+                    b')' if arity == 0 => self.emit(Instruction::new(id, &[]), emit),
+                    b')' if complete && index + 1 == arity => {
+                        self.args[index] = value;
+                        self.emit(Instruction::new(id, &self.args[..arity]), emit)
+                    }
+                    _ => return None,
+                }
+            }
+        };
+
+        Some(state)
+    }
+
+    fn emit(&self, instruction: Instruction, emit: &mut impl FnMut(Token)) -> State {
+        emit(Token {
+            instruction,
+            span: self.start..self.offset + 1,
+        });
+        State::Idle
+    }
+}
+
+fn tokenize(set: &InstructionSet, memory: &[u8]) -> Vec<Token> {
+    let mut tokens = Vec::new();
+    Tokenizer::new(set).feed(memory, |token| tokens.push(token));
+
+    tokens
+}
+
+fn parse_input(set: &InstructionSet) -> Vec<Instruction> {
+    let mut instructions = Vec::new();
+    Tokenizer::new(set).feed(read_input!(03).as_bytes(), |token| {
+        instructions.push(token.instruction)
+    });
+
+    instructions
+}
+
+/// The regex the tokenizer replaced, kept to benchmark against with the default instructions.
+#[cfg(feature = "regex")]
+fn parse_input_regex() -> Vec<Instruction> {
+    let re = Regex::new(r"mul\((\d{1,3}),(\d{1,3})\)|do\(\)|don't\(\)").unwrap();
+    let [mul, enable, disable] = ["mul", "do", "don't"].map(|name| definition_id(name).unwrap());
+
+    re.captures_iter(read_input!(03))
+        .map(|c| match (c.get(1), c.get(2)) {
+            (Some(a), Some(b)) => Instruction::new(
+                mul,
+                &[a.as_str().parse().unwrap(), b.as_str().parse().unwrap()],
+            ),
+            _ if &c[0] == "do()" => Instruction::new(enable, &[]),
+            _ => Instruction::new(disable, &[]),
         })
         .collect()
 }
+
+/// The recognized instructions and the enabled and disabled spans of the memory.
+pub fn explain() -> Explanation {
+    let params = Params::load();
+    let set = params.instruction_set();
+    let memory = read_input!(03).as_bytes();
+    let tokens = tokenize(&set, memory);
+    let mut explanation = Explanation::default();
+
+    explanation.section(format!(
+        "Instructions with {} to {} digit operands",
+        set.digits.start(),
+        set.digits.end()
+    ));
+    for name in params.names() {
+        let definition = &INSTRUCTIONS[definition_id(name).unwrap()];
+        let count = tokens
+            .iter()
+            .filter(|t| t.instruction.definition().name == name)
+            .count();
+        explanation.entry(
+            format!("{}/{}", name, definition.arity),
+            format!("{} found", count),
+        );
+    }
+
+    explanation.section("Spans");
+    for span in trace(&tokens, memory.len()) {
+        explanation.entry(
+            format!("bytes {}..{}", span.range.start, span.range.end),
+            format!(
+                "{}, {} values, sum {}",
+                if span.enabled { "enabled" } else { "disabled" },
+                span.count,
+                span.sum
+            ),
+        );
+    }
+
+    explanation
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn tokenize_chunks(set: &InstructionSet, chunks: &[&[u8]]) -> Vec<Token> {
+        let mut tokenizer = Tokenizer::new(set);
+        let mut tokens = Vec::new();
+        for chunk in chunks {
+            tokenizer.feed(chunk, |token| tokens.push(token));
+        }
+        tokens
+    }
+
+    fn names(tokens: &[Token]) -> Vec<String> {
+        tokens.iter().map(|t| t.instruction.to_string()).collect()
+    }
+
+    #[test]
+    fn tokenizer_test() {
+        let set = InstructionSet::new(&["mul", "do", "don't"], 1..=3);
+        let memory = b"xmul(2,4)&mul[3,7]!^don't()_mul(5,5)+mumul(32,64](mul(11,8)undo()?mul(8,5))mul(1234,5)dodon't()";
+        let tokens = tokenize_chunks(&set, &[memory]);
+        assert_eq!(
+            vec![
+                "mul(2,4)",
+                "don't()",
+                "mul(5,5)",
+                "mul(11,8)",
+                "do()",
+                "mul(8,5)",
+                "don't()"
+            ],
+            names(&tokens)
+        );
+        assert_eq!(1..9, tokens[0].span);
+        assert_eq!(b"don't()", &memory[tokens[1].span.clone()]);
+
+        // every split into two buffers finds the same tokens
+        for i in 0..memory.len() {
+            assert_eq!(tokens, tokenize_chunks(&set, &[&memory[..i], &memory[i..]]));
+        }
+    }
+
+    #[test]
+    fn instruction_set_test() {
+        let set = InstructionSet::new(&["mul", "add", "neg", "do"], 2..=5);
+        let memory = b"mul(1,2)mul(12,34)add(1234,56789)neg(99)neg(1,2)add(123456,10)addo()";
+        let tokens = tokenize_chunks(&set, &[memory]);
+        assert_eq!(
+            vec!["mul(12,34)", "add(1234,56789)", "neg(99)", "do()"],
+            names(&tokens)
+        );
+        let input: Vec<Instruction> = tokens.iter().map(|t| t.instruction).collect();
+        assert_eq!(12 * 34 + 1234 + 56789 - 99, part1(&input));
+
+        // the `d` of `add` starts `do`, failed matches are scanned again
+        assert!(set.look_back);
+        assert_eq!(memory.len() - 4..memory.len(), tokens[3].span);
+        for i in 0..memory.len() {
+            assert_eq!(tokens, tokenize_chunks(&set, &[&memory[..i], &memory[i..]]));
+        }
+
+        // operands that overflow are not matched
+        let set = InstructionSet::new(&["mul"], 1..=20);
+        let tokens = tokenize(&set, b"mul(98765432109876543210,2)mul(123456789012,2)");
+        assert_eq!(vec!["mul(123456789012,2)"], names(&tokens));
+    }
+
+    #[test]
+    fn trace_test() {
+        let set = InstructionSet::new(&["mul", "do", "don't"], 1..=3);
+        let memory = b"mul(2,4)don't()mul(5,5)don't()do()mul(8,5)do()";
+        let spans = trace(&tokenize(&set, memory), memory.len());
+        assert_eq!(
+            vec![(true, 0..8, 8), (false, 8..30, 25), (true, 30..46, 40)],
+            spans
+                .iter()
+                .map(|s| (s.enabled, s.range.clone(), s.sum))
+                .collect::<Vec<_>>()
+        );
+    }
+}
@@ -1,101 +1,449 @@
-use std::cmp::{max, min};
+use std::fmt::{Display, Formatter};
 
+use crate::etc::bench::Bench;
+use crate::etc::explain::Explanation;
+use crate::etc::params;
+use crate::etc::visualize::{Colour, Frame, Frames};
+use crate::utils::automaton::AhoCorasick;
+use crate::utils::hash::{FastMap, FastSet};
 use crate::{read_input, Solution, SolutionPair};
 
-static XMAS: &[u8] = "XMAS".as_bytes();
-static A: u8 = 65;
-static M: u8 = 77;
-static S: u8 = 83;
-static DIRS: &[(i8, i8)] = &[
-    (1, 0),
-    (1, 1),
-    (0, 1),
-    (-1, 1),
-    (-1, 0),
-    (-1, -1),
-    (0, -1),
-    (1, -1),
+/// Directions `(di, dj)` a word can be read in, clockwise from the right.
+static DIRECTIONS: &[((isize, isize), &str)] = &[
+    ((0, 1), "right"),
+    ((1, 1), "down-right"),
+    ((1, 0), "down"),
+    ((1, -1), "down-left"),
+    ((0, -1), "left"),
+    ((-1, -1), "up-left"),
+    ((-1, 0), "up"),
+    ((-1, 1), "up-right"),
 ];
 
 pub fn solve() -> SolutionPair {
+    let params = Params::load();
     let lines = parse_input();
-    let sol1: u32 = part1(&lines);
-    let sol2: u32 = part2(&lines);
+    let sol1: usize = part1(&lines, &params.words());
+    let sol2: usize = part2(&lines, &params.shapes());
 
     (Solution::from(sol1), Solution::from(sol2))
 }
 
-fn part1(lines: &Vec<Vec<u8>>) -> u32 {
-    let mut result = 0;
-    for i in 0..lines.len() {
-        for j in 0..lines[i].len() {
-            result += search_at(XMAS, (i, j), lines);
+pub fn bench(bench: &mut Bench) {
+    let params = Params::load();
+    let (words, shapes) = (params.words(), params.shapes());
+    bench.run("parse", parse_input);
+    let lines = parse_input();
+    bench.run("part1", || part1(&lines, &words));
+    bench.run("part2", || part2(&lines, &shapes));
+}
+
+struct Params {
+    words: String,
+    shapes: String,
+}
+
+impl Params {
+    fn load() -> Self {
+        Self {
+            words: params::get(4, "words", String::from("XMAS")),
+            shapes: params::get(4, "shapes", String::from("M.S/.A./M.S")),
         }
     }
 
-    result
+    /// Search for the comma separated words.
+    fn words(&self) -> WordSearch {
+        WordSearch::new(self.words.split(',').map(Pattern::word).collect())
+    }
+
+    /// Search for the comma separated shapes, whose rows are separated by `/`.
+    fn shapes(&self) -> WordSearch {
+        WordSearch::new(self.shapes.split(',').map(Pattern::shape).collect())
+    }
 }
 
-fn part2(lines: &Vec<Vec<u8>>) -> u32 {
-    let mut result = 0;
-    for i in 1..lines.len() - 1 {
-        for j in 1..lines[i].len() - 1 {
-            if lines[i][j] == A {
-                if max(lines[i - 1][j - 1], lines[i + 1][j + 1]) == S
-                    && min(lines[i - 1][j - 1], lines[i + 1][j + 1]) == M
-                    && max(lines[i - 1][j + 1], lines[i + 1][j - 1]) == S
-                    && min(lines[i - 1][j + 1], lines[i + 1][j - 1]) == M
-                {
-                    result += 1;
-                }
+fn part1(lines: &[Vec<u8>], words: &WordSearch) -> usize {
+    words.find(lines).len()
+}
+
+fn part2(lines: &[Vec<u8>], shapes: &WordSearch) -> usize {
+    shapes.find(lines).len()
+}
+
+/// Word or shape to search the grid for.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Pattern {
+    /// Letters in a straight line, read in any of the 8 directions.
+    Word(Vec<u8>),
+    /// Rows of letters where `.` matches anything, in any rotation or reflection.
+    Shape(Vec<Vec<u8>>),
+}
+
+impl Pattern {
+    pub fn word(word: &str) -> Self {
+        if word.is_empty() {
+            panic!("Words need at least one letter");
+        }
+        Pattern::Word(word.bytes().collect())
+    }
+
+    /// Shape from its rows separated by `/`, e.g. `M.S/.A./M.S` for the X-shaped `MAS`.
+    pub fn shape(shape: &str) -> Self {
+        if shape.bytes().all(|c| c == b'.' || c == b'/') {
+            panic!("Shapes need at least one letter: {}", shape);
+        }
+        Pattern::Shape(shape.split('/').map(|row| row.bytes().collect()).collect())
+    }
+}
+
+impl Display for Pattern {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Pattern::Word(word) => write!(f, "{}", String::from_utf8_lossy(word)),
+            Pattern::Shape(rows) => {
+                let rows: Vec<_> = rows.iter().map(|r| String::from_utf8_lossy(r)).collect();
+                write!(f, "{}", rows.join("/"))
             }
         }
     }
+}
 
-    result
+/// How a match lies in the grid.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Orientation {
+    /// Direction `(di, dj)` the word is read in.
+    Direction(isize, isize),
+    /// Clockwise quarter turns of the shape, after mirroring it left to right if `reflected`.
+    Transform { turns: u8, reflected: bool },
 }
 
-fn search_at(word: &[u8], pos: (usize, usize), lines: &Vec<Vec<u8>>) -> u32 {
-    let mut result = 0;
-    for dir in DIRS {
-        if search_at_dir(word, pos, *dir, lines) {
-            result += 1;
+impl Display for Orientation {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match *self {
+            Orientation::Direction(di, dj) => {
+                let (_, name) = DIRECTIONS.iter().find(|(d, _)| *d == (di, dj)).unwrap();
+                write!(f, "{}", name)
+            }
+            Orientation::Transform { turns, reflected } => match (reflected, turns) {
+                (false, 0) => write!(f, "as given"),
+                (false, _) => write!(f, "turned {}°", 90 * turns as u32),
+                (true, 0) => write!(f, "mirrored"),
+                (true, _) => write!(f, "mirrored, turned {}°", 90 * turns as u32),
+            },
         }
     }
+}
+
+/// Pattern found in the grid.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Match {
+    /// Index of the pattern in the search.
+    pub pattern: usize,
+    /// Cell of the first letter of the pattern as written, `(i, j)`.
+    pub position: (usize, usize),
+    pub orientation: Orientation,
+}
+
+/// One distinct rotation or reflection of a shape: its letters relative to the first one.
+#[derive(Clone, Debug)]
+struct Variant {
+    pattern: usize,
+    orientation: Orientation,
+    cells: Vec<((isize, isize), u8)>,
+}
 
-    result
+/// Word Search
+///
+/// Finds words and shapes in a grid of letters. All words are searched at once by running one
+/// Aho-Corasick automaton, which also knows every word spelled backwards, along each row,
+/// column and diagonal. Shapes are tried in every distinct rotation and reflection at the cells
+/// holding their first letter.
+///
+/// Every occurrence is found once, even for palindromes and symmetric shapes.
+#[derive(Clone, Debug)]
+pub struct WordSearch {
+    patterns: Vec<Pattern>,
+    automaton: AhoCorasick,
+    // automaton pattern -> searched pattern and whether it is spelled backwards
+    words: Vec<(usize, bool)>,
+    variants: Vec<Variant>,
 }
 
-fn search_at_dir(
-    word: &[u8],
-    (i, j): (usize, usize),
-    (di, dj): (i8, i8),
-    lines: &Vec<Vec<u8>>,
-) -> bool {
-    let (mut i_opt, mut j_opt) = (Some(i), Some(j));
-    for k in 0..word.len() {
-        if i_opt.is_none() || j_opt.is_none() {
-            return false;
+impl WordSearch {
+    pub fn new(patterns: Vec<Pattern>) -> Self {
+        let mut spellings = Vec::new();
+        let mut words = Vec::new();
+        let mut variants = Vec::new();
+        for (id, pattern) in patterns.iter().enumerate() {
+            match pattern {
+                Pattern::Word(word) => {
+                    let backwards: Vec<u8> = word.iter().rev().copied().collect();
+                    words.push((id, false));
+                    if backwards != *word {
+                        words.push((id, true));
+                        spellings.push(word.clone());
+                        spellings.push(backwards);
+                    } else {
+                        spellings.push(word.clone());
+                    }
+                }
+                Pattern::Shape(rows) => variants.extend(shape_variants(id, rows)),
+            }
         }
-        let (i, j) = (i_opt.unwrap(), j_opt.unwrap());
 
-        if i == lines.len() || j == lines[i].len() || word[k] != lines[i][j] {
-            return false;
+        Self {
+            patterns,
+            automaton: AhoCorasick::new(&spellings),
+            words,
+            variants,
         }
+    }
+
+    pub fn patterns(&self) -> &[Pattern] {
+        &self.patterns
+    }
 
-        if di < 0 {
-            i_opt = i.checked_sub(di.abs() as usize);
-        } else {
-            i_opt = i.checked_add(di as usize);
+    /// Cells `(i, j)` covered by the match, in the order of the letters of its pattern.
+    pub fn cells(&self, m: &Match) -> Vec<(usize, usize)> {
+        let (i, j) = (m.position.0 as isize, m.position.1 as isize);
+        let offsets: Vec<(isize, isize)> = match (&self.patterns[m.pattern], m.orientation) {
+            (Pattern::Word(word), Orientation::Direction(di, dj)) => {
+                (0..word.len() as isize).map(|k| (k * di, k * dj)).collect()
+            }
+            _ => self
+                .variants
+                .iter()
+                .find(|v| v.pattern == m.pattern && v.orientation == m.orientation)
+                .expect("Matches of shapes have the orientation of a variant")
+                .cells
+                .iter()
+                .map(|(offset, _)| *offset)
+                .collect(),
+        };
+
+        offsets
+            .into_iter()
+            .map(|(di, dj)| ((i + di) as usize, (j + dj) as usize))
+            .collect()
+    }
+
+    /// Every match in the grid, ordered by position and pattern.
+    pub fn find(&self, grid: &[Vec<u8>]) -> Vec<Match> {
+        let mut found = Vec::new();
+        if !self.words.is_empty() {
+            self.find_words(grid, &mut found);
         }
-        if dj < 0 {
-            j_opt = j.checked_sub(dj.abs() as usize);
-        } else {
-            j_opt = j.checked_add(dj as usize);
+        self.find_shapes(grid, &mut found);
+
+        found.sort_by_key(|m| (m.position, m.pattern));
+        found
+    }
+
+    fn find_words(&self, grid: &[Vec<u8>], found: &mut Vec<Match>) {
+        let (height, width) = (grid.len(), grid.first().map_or(0, |row| row.len()));
+        let at = |i: usize, j: isize| grid.get(i).and_then(|row| row.get(j as usize));
+
+        // one line through each cell of the first row or column for right, down and diagonals
+        let families: [(_, Vec<(usize, isize)>); 4] = [
+            ((0, 1), (0..height).map(|i| (i, 0)).collect()),
+            ((1, 0), (0..width).map(|j| (0, j as isize)).collect()),
+            (
+                (1, 1),
+                (1..height)
+                    .rev()
+                    .map(|i| (i, 0))
+                    .chain((0..width).map(|j| (0, j as isize)))
+                    .collect(),
+            ),
+            (
+                (1, -1),
+                (0..width)
+                    .map(|j| (0, j as isize))
+                    .chain((1..height).map(|i| (i, width as isize - 1)))
+                    .collect(),
+            ),
+        ];
+
+        for (family, ((di, dj), starts)) in families.into_iter().enumerate() {
+            for (mut i, mut j) in starts {
+                let mut state = AhoCorasick::START;
+                while let Some(&c) = at(i, j) {
+                    state = self.automaton.step(state, c);
+                    for &spelling in self.automaton.matches(state) {
+                        let len = self.automaton.pattern_len(spelling) as isize;
+                        // single letters lie on a line of every family
+                        if len == 1 && family > 0 {
+                            continue;
+                        }
+
+                        let (pattern, backwards) = self.words[spelling];
+                        let (position, orientation) = if backwards {
+                            ((i, j as usize), Orientation::Direction(-di, -dj))
+                        } else {
+                            (
+                                (
+                                    (i as isize - (len - 1) * di) as usize,
+                                    (j - (len - 1) * dj) as usize,
+                                ),
+                                Orientation::Direction(di, dj),
+                            )
+                        };
+                        found.push(Match {
+                            pattern,
+                            position,
+                            orientation,
+                        });
+                    }
+                    i += di as usize;
+                    j += dj;
+                }
+            }
+        }
+    }
+
+    fn find_shapes(&self, grid: &[Vec<u8>], found: &mut Vec<Match>) {
+        for (i, row) in grid.iter().enumerate() {
+            for (j, &c) in row.iter().enumerate() {
+                for variant in &self.variants {
+                    let matches = variant.cells.iter().all(|&((di, dj), letter)| {
+                        let (i, j) = (i as isize + di, j as isize + dj);
+                        i >= 0
+                            && j >= 0
+                            && grid
+                                .get(i as usize)
+                                .and_then(|row| row.get(j as usize))
+                                .is_some_and(|&c| c == letter)
+                    });
+                    if variant.cells[0].1 == c && matches {
+                        found.push(Match {
+                            pattern: variant.pattern,
+                            position: (i, j),
+                            orientation: variant.orientation,
+                        });
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// The distinct rotations and reflections of the shape, the first letter of the shape as given
+/// is the first cell of each.
+fn shape_variants(pattern: usize, rows: &[Vec<u8>]) -> Vec<Variant> {
+    let letters: Vec<((isize, isize), u8)> = rows
+        .iter()
+        .enumerate()
+        .flat_map(|(i, row)| {
+            row.iter()
+                .enumerate()
+                .filter(|(_, c)| **c != b'.')
+                .map(move |(j, c)| ((i as isize, j as isize), *c))
+        })
+        .collect();
+    let (first_i, first_j) = letters[0].0;
+
+    let mut variants: Vec<Variant> = Vec::new();
+    let mut seen = FastSet::default();
+    for reflected in [false, true] {
+        for turns in 0..4 {
+            let cells: Vec<((isize, isize), u8)> = letters
+                .iter()
+                .map(|&((i, j), c)| {
+                    let (mut i, mut j) = (i - first_i, j - first_j);
+                    if reflected {
+                        j = -j;
+                    }
+                    for _ in 0..turns {
+                        (i, j) = (j, -i);
+                    }
+                    ((i, j), c)
+                })
+                .collect();
+
+            // symmetric shapes cover the same cells in several orientations
+            let min_i = cells.iter().map(|((i, _), _)| *i).min().unwrap();
+            let min_j = cells.iter().map(|((_, j), _)| *j).min().unwrap();
+            let mut key: Vec<_> = cells
+                .iter()
+                .map(|&((i, j), c)| ((i - min_i, j - min_j), c))
+                .collect();
+            key.sort();
+            if seen.insert(key) {
+                variants.push(Variant {
+                    pattern,
+                    orientation: Orientation::Transform { turns, reflected },
+                    cells,
+                });
+            }
+        }
+    }
+
+    variants
+}
+
+/// Colours of the matches, by pattern.
+static HIGHLIGHTS: &[Colour] = &[Colour::Yellow, Colour::Green, Colour::Red, Colour::White];
+
+/// The grid with only the letters of the matches, coloured by pattern, and `.` everywhere else.
+pub fn highlight(title: String, grid: &[Vec<u8>], search: &WordSearch, found: &[Match]) -> Frame {
+    let width = grid.iter().map(|row| row.len()).max().unwrap_or(0);
+    let mut frame = Frame::new(title, grid.len(), width);
+    for m in found {
+        let colour = HIGHLIGHTS[m.pattern % HIGHLIGHTS.len()];
+        for (i, j) in search.cells(m) {
+            frame.set(i, j, grid[i][j] as char, colour);
+        }
+    }
+
+    frame
+}
+
+/// The matches of the words, then those of the shapes.
+pub fn visualize() -> Frames {
+    let params = Params::load();
+    let lines = parse_input();
+    let frames: Vec<Frame> = [params.words(), params.shapes()]
+        .iter()
+        .map(|search| {
+            let found = search.find(&lines);
+            let patterns: Vec<String> = search.patterns().iter().map(|p| p.to_string()).collect();
+            let title = format!(
+                "Day 04 - {} matches of {}",
+                found.len(),
+                patterns.join(", ")
+            );
+            highlight(title, &lines, search, &found)
+        })
+        .collect();
+
+    Box::new(frames.into_iter())
+}
+
+/// Number of matches of every pattern in every orientation.
+pub fn explain() -> Explanation {
+    let params = Params::load();
+    let lines = parse_input();
+    let mut explanation = Explanation::default();
+
+    for (title, search) in [("Words", params.words()), ("Shapes", params.shapes())] {
+        let mut counts: FastMap<(usize, Orientation), usize> = FastMap::default();
+        let found = search.find(&lines);
+        for m in &found {
+            *counts.entry((m.pattern, m.orientation)).or_default() += 1;
+        }
+        let mut counts: Vec<_> = counts.into_iter().collect();
+        counts.sort_by_key(|((pattern, _), count)| (*pattern, usize::MAX - count));
+
+        explanation.section(format!("{} ({} matches)", title, found.len()));
+        for ((pattern, orientation), count) in counts {
+            explanation.entry(
+                format!("{} {}", search.patterns()[pattern], orientation),
+                count,
+            );
         }
     }
 
-    true
+    explanation
 }
 
 fn parse_input() -> Vec<Vec<u8>> {
@@ -106,3 +454,95 @@ fn parse_input() -> Vec<Vec<u8>> {
         .map(|line| line.bytes().collect())
         .collect()
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn grid(rows: &[&str]) -> Vec<Vec<u8>> {
+        rows.iter().map(|row| row.bytes().collect()).collect()
+    }
+
+    #[test]
+    fn words_test() {
+        let grid = grid(&["ABCA", "XBAB", "CXAC"]);
+        let search = WordSearch::new(vec![
+            Pattern::word("ABC"),
+            Pattern::word("ABA"),
+            Pattern::word("X"),
+            Pattern::word("BX"),
+        ]);
+        let found: Vec<(usize, (usize, usize), String)> = search
+            .find(&grid)
+            .iter()
+            .map(|m| (m.pattern, m.position, m.orientation.to_string()))
+            .collect();
+        assert_eq!(
+            vec![
+                (0, (0, 0), String::from("right")),
+                (1, (0, 0), String::from("down-right")),
+                (3, (0, 1), String::from("down-left")),
+                (0, (0, 3), String::from("down")),
+                (2, (1, 0), String::from("right")),
+                (3, (1, 1), String::from("left")),
+                (3, (1, 1), String::from("down")),
+                (2, (2, 1), String::from("right")),
+            ],
+            found
+        );
+    }
+
+    #[test]
+    fn highlight_test() {
+        let example = grid(&[
+            "MMMSXXMASM",
+            "MSAMXMSMSA",
+            "AMXSXMAAMM",
+            "MSAMASMSMX",
+            "XMASAMXAMM",
+            "XXAMMXXAMA",
+            "SMSMSASXSS",
+            "SAXAMASAAA",
+            "MAMMMXMMMM",
+            "MXMXAXMASX",
+        ]);
+        let render = |search: WordSearch| {
+            let found = search.find(&example);
+            highlight(String::new(), &example, &search, &found).plain()
+        };
+
+        assert_eq!(
+            "....XXMAS.\n.SAMXMS...\n...S..A...\n..A.A.MS.X\nXMASAMX.MM\n\
+             X.....XA.A\nS.S.S.S.SS\n.A.A.A.A.A\n..M.M.M.MM\n.X.X.XMASX\n",
+            render(WordSearch::new(vec![Pattern::word("XMAS")]))
+        );
+        assert_eq!(
+            ".M.S......\n..A..MSMS.\n.M.S.MAA..\n..A.ASMSM.\n.M.S.M....\n\
+             ..........\nS.S.S.S.S.\n.A.A.A.A..\nM.M.M.M.M.\n..........\n",
+            render(WordSearch::new(vec![Pattern::shape("M.S/.A./M.S")]))
+        );
+    }
+
+    #[test]
+    fn shapes_test() {
+        // the L-shape in all of its 8 orientations, the symmetric cross only once
+        let search = WordSearch::new(vec![Pattern::shape("A./BC"), Pattern::shape(".A./AAA/.A.")]);
+        assert_eq!(8, search.variants.iter().filter(|v| v.pattern == 0).count());
+        assert_eq!(1, search.variants.iter().filter(|v| v.pattern == 1).count());
+
+        let grid = grid(&["A..BA", "BC.C.", "..A..", ".AAA.", "..A.."]);
+        let found: Vec<(usize, (usize, usize), String)> = search
+            .find(&grid)
+            .iter()
+            .map(|m| (m.pattern, m.position, m.orientation.to_string()))
+            .collect();
+        assert_eq!(
+            vec![
+                (0, (0, 0), String::from("as given")),
+                (0, (0, 4), String::from("turned 90°")),
+                (1, (2, 2), String::from("as given")),
+            ],
+            found
+        );
+    }
+}
@@ -1,7 +1,17 @@
-use std::collections::{HashMap, HashSet};
+use std::collections::BTreeSet;
+use std::fmt::Display;
+use std::fs;
+use std::hash::Hash;
+use std::rc::Rc;
 
+use crate::etc::bench::Bench;
+use crate::etc::explain::Explanation;
+use crate::etc::repl::{arg, Repl};
+use crate::utils::hash::{FastMap, FastSet};
 use crate::{read_input, Solution, SolutionPair};
 
+pub type Page = u16;
+
 pub fn solve() -> SolutionPair {
     let (rules, pages) = parse_input();
     let (sol1, sol2) = parts(&rules, &pages);
@@ -9,91 +19,509 @@ pub fn solve() -> SolutionPair {
     (Solution::from(sol1), Solution::from(sol2))
 }
 
-fn parts(rules: &HashMap<u8, HashSet<u8>>, pages: &Vec<Vec<u8>>) -> (u32, u32) {
+/// Both parts are solved together.
+pub fn bench(bench: &mut Bench) {
+    bench.run("parse", parse_input);
+    let (rules, pages) = parse_input();
+    bench.run("parts", || parts(&rules, &pages));
+}
+
+fn parts(rules: &Rules<Page>, pages: &Vec<Vec<Page>>) -> (u32, u32) {
     let mut result_1 = 0;
     let mut result_2 = 0;
     for page in pages {
-        let mut els: HashSet<u8> = HashSet::from_iter(page.iter().cloned());
-        if verify_page(page, &els, rules) {
+        if rules.is_ordered(page) {
             result_1 += page[page.len() / 2] as u32;
         } else {
-            let el = generate_sorted_mid(&mut els, rules);
-            result_2 += el as u32;
+            let sorted = rules.order(page).unwrap_or_else(|cycle| {
+                panic!(
+                    "Update {} has no valid order, its rules form the cycle {}",
+                    join(page, ","),
+                    format_cycle(&cycle)
+                )
+            });
+            result_2 += sorted.pages[sorted.pages.len() / 2] as u32;
         }
     }
 
     (result_1, result_2)
 }
 
-fn verify_page(page: &Vec<u8>, els: &HashSet<u8>, rules: &HashMap<u8, HashSet<u8>>) -> bool {
-    let mut visited = HashSet::new();
-    for el in page {
-        if !verify_el(*el, &visited, els, rules) {
-            return false;
+/// Identifier of a page, e.g. `u16` or `&str` for arbitrary labels.
+pub trait PageId: Copy + Eq + Hash + Ord + Display {}
+
+impl<T: Copy + Eq + Hash + Ord + Display> PageId for T {}
+
+/// Update in an order that follows all the rules between its pages.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Sorted<P> {
+    pub pages: Vec<P>,
+    /// No other order of the pages follows the rules.
+    pub unique: bool,
+}
+
+/// Rules
+///
+/// For every page the pages that have to be printed before it, if both are in the update.
+#[derive(Clone, Debug)]
+pub struct Rules<P> {
+    before: FastMap<P, FastSet<P>>,
+}
+
+impl<P: PageId> Rules<P> {
+    /// Rules from `(earlier, later)` pairs.
+    pub fn new(pairs: impl IntoIterator<Item = (P, P)>) -> Self {
+        let mut before: FastMap<P, FastSet<P>> = FastMap::default();
+        for (earlier, later) in pairs {
+            before.entry(later).or_default().insert(earlier);
         }
-        visited.insert(*el);
+
+        Self { before }
+    }
+
+    /// Pages that have to be printed before `page`.
+    pub fn before(&self, page: P) -> Option<&FastSet<P>> {
+        self.before.get(&page)
     }
 
-    true
+    fn requires(&self, later: P, earlier: P) -> bool {
+        self.before
+            .get(&later)
+            .is_some_and(|before| before.contains(&earlier))
+    }
+
+    /// No page of the update is printed before one that the rules require before it.
+    pub fn is_ordered(&self, update: &[P]) -> bool {
+        update.iter().enumerate().all(|(i, &page)| {
+            update[i + 1..]
+                .iter()
+                .all(|&later| !self.requires(page, later))
+        })
+    }
+
+    /// Orders the update by the rules between its pages, keeping the given order where they
+    /// leave a choice. When no order exists, the error holds pages that form a cycle, each one
+    /// required before the next and the last before the first, starting with the smallest.
+    pub fn order(&self, update: &[P]) -> Result<Sorted<P>, Vec<P>> {
+        let n = update.len();
+        // edges between the positions of the update, earlier -> later
+        let mut later: Vec<Vec<usize>> = vec![Vec::new(); n];
+        let mut required = vec![0; n];
+        for (i, &a) in update.iter().enumerate() {
+            for (j, &b) in update.iter().enumerate() {
+                if self.requires(b, a) {
+                    later[i].push(j);
+                    required[j] += 1;
+                }
+            }
+        }
+
+        let mut ready: BTreeSet<usize> = (0..n).filter(|&i| required[i] == 0).collect();
+        let mut pages = Vec::with_capacity(n);
+        let mut unique = true;
+        while let Some(i) = ready.pop_first() {
+            unique &= ready.is_empty();
+            pages.push(update[i]);
+            for &j in &later[i] {
+                required[j] -= 1;
+                if required[j] == 0 {
+                    ready.insert(j);
+                }
+            }
+        }
+
+        if pages.len() == n {
+            return Ok(Sorted { pages, unique });
+        }
+
+        // every page left is still waiting for another page left, going back through them has
+        // to come around to a page seen before
+        let mut seen = vec![None; n];
+        let mut path = Vec::new();
+        let mut i = (0..n).find(|&i| required[i] > 0).unwrap();
+        while seen[i].is_none() {
+            seen[i] = Some(path.len());
+            path.push(i);
+            i = (0..n)
+                .find(|&j| required[j] > 0 && later[j].contains(&i))
+                .unwrap();
+        }
+
+        let mut cycle: Vec<P> = path[seen[i].unwrap()..]
+            .iter()
+            .map(|&i| update[i])
+            .collect();
+        cycle.reverse();
+        let (smallest, _) = cycle.iter().enumerate().min_by_key(|(_, p)| **p).unwrap();
+        cycle.rotate_left(smallest);
+        Err(cycle)
+    }
 }
 
-fn verify_el(
-    el: u8,
-    visited: &HashSet<u8>,
-    els: &HashSet<u8>,
-    rules: &HashMap<u8, HashSet<u8>>,
-) -> bool {
-    if let Some(reqs) = rules.get(&el) {
-        for req in reqs {
-            if els.contains(req) && !visited.contains(req) {
-                return false;
+impl<P: PageId> Rules<P> {
+    /// Pages of the rules in order, and for every page the positions of the pages it is
+    /// required before.
+    fn graph(&self) -> (Vec<P>, Vec<Vec<usize>>) {
+        let mut pages: Vec<P> = self
+            .before
+            .iter()
+            .flat_map(|(later, before)| before.iter().chain([later]))
+            .copied()
+            .collect();
+        pages.sort();
+        pages.dedup();
+
+        let index = |page: &P| pages.binary_search(page).unwrap();
+        let mut later = vec![Vec::new(); pages.len()];
+        for (page, before) in &self.before {
+            for earlier in before {
+                later[index(earlier)].push(index(page));
             }
         }
+        later.iter_mut().for_each(|next| next.sort());
+
+        (pages, later)
     }
 
-    true
-}
+    /// Every rule as `(earlier, later)`, ordered.
+    pub fn pairs(&self) -> Vec<(P, P)> {
+        let (pages, later) = self.graph();
+        later
+            .iter()
+            .enumerate()
+            .flat_map(|(i, next)| next.iter().map(move |&j| (i, j)))
+            .map(|(i, j)| (pages[i], pages[j]))
+            .collect()
+    }
+
+    /// Groups of pages that all require each other through chains of rules, so no update can
+    /// hold all of a group's pages. The strongly connected components of the rules with more
+    /// than one page or a page required before itself, ordered and with their pages ordered.
+    pub fn cycles(&self) -> Vec<Vec<P>> {
+        let (pages, later) = self.graph();
+        let n = pages.len();
+        let mut earlier = vec![Vec::new(); n];
+        for (i, next) in later.iter().enumerate() {
+            for &j in next {
+                earlier[j].push(i);
+            }
+        }
+
+        // Kosaraju: finish order along the rules, then components against them
+        let mut visited = vec![false; n];
+        let mut finished = Vec::with_capacity(n);
+        for root in 0..n {
+            if visited[root] {
+                continue;
+            }
+            visited[root] = true;
+            let mut stack = vec![(root, 0)];
+            while let Some((i, k)) = stack.pop() {
+                match later[i].get(k) {
+                    Some(&j) => {
+                        stack.push((i, k + 1));
+                        if !visited[j] {
+                            visited[j] = true;
+                            stack.push((j, 0));
+                        }
+                    }
+                    None => finished.push(i),
+                }
+            }
+        }
+
+        let mut component = vec![usize::MAX; n];
+        let mut cycles = Vec::new();
+        for &root in finished.iter().rev() {
+            if component[root] != usize::MAX {
+                continue;
+            }
+            component[root] = root;
+            let mut members = vec![root];
+            let mut stack = vec![root];
+            while let Some(i) = stack.pop() {
+                for &j in &earlier[i] {
+                    if component[j] == usize::MAX {
+                        component[j] = root;
+                        members.push(j);
+                        stack.push(j);
+                    }
+                }
+            }
 
-fn generate_sorted_mid(els: &mut HashSet<u8>, rules: &HashMap<u8, HashSet<u8>>) -> u8 {
-    let mut el = 0;
-    for _ in 0..=(els.len() / 2) {
-        el = get_next_el(els, rules);
-        els.remove(&el);
+            if members.len() > 1 || later[root].contains(&root) {
+                let mut members: Vec<P> = members.iter().map(|&i| pages[i]).collect();
+                members.sort();
+                cycles.push(members);
+            }
+        }
+        cycles.sort();
+
+        cycles
     }
 
-    el
+    /// Rules implied by chains of other rules, a transitive reduction keeps the rest. Without
+    /// them every page is still required after the same pages. Within cycles rules imply each
+    /// other, there the ones checked first, in order, are dropped.
+    pub fn redundant(&self) -> Vec<(P, P)> {
+        let (pages, mut later) = self.graph();
+        let mut redundant = Vec::new();
+        for i in 0..pages.len() {
+            for j in later[i].clone() {
+                later[i].retain(|&k| k != j);
+                if i != j && reachable(&later, i, j) {
+                    redundant.push((pages[i], pages[j]));
+                } else {
+                    later[i].push(j);
+                }
+            }
+        }
+        redundant.sort();
+
+        redundant
+    }
+
+    /// Pages of the updates that are in no rule, in order.
+    pub fn unruled(&self, updates: &[Vec<P>]) -> Vec<P> {
+        let (pages, _) = self.graph();
+        let mut unruled: Vec<P> = updates
+            .iter()
+            .flatten()
+            .filter(|page| pages.binary_search(page).is_err())
+            .copied()
+            .collect();
+        unruled.sort();
+        unruled.dedup();
+
+        unruled
+    }
+
+    /// The rules as a Graphviz digraph, with redundant rules dashed and pages on cycles red.
+    pub fn to_dot(&self) -> String {
+        let quote = |page: &P| format!("\"{}\"", page.to_string().replace('"', "\\\""));
+        let redundant: FastSet<(P, P)> = self.redundant().into_iter().collect();
+
+        let mut dot = String::from("digraph rules {\n");
+        for page in self.cycles().iter().flatten() {
+            dot.push_str(&format!("    {} [color=red];\n", quote(page)));
+        }
+        for (earlier, later) in self.pairs() {
+            let style = if redundant.contains(&(earlier, later)) {
+                " [style=dashed]"
+            } else {
+                ""
+            };
+            dot.push_str(&format!(
+                "    {} -> {}{};\n",
+                quote(&earlier),
+                quote(&later),
+                style
+            ));
+        }
+        dot.push_str("}\n");
+
+        dot
+    }
 }
 
-fn get_next_el(els: &HashSet<u8>, rules: &HashMap<u8, HashSet<u8>>) -> u8 {
-    for el in els {
-        if let Some(reqs) = rules.get(el) {
-            if els.is_disjoint(reqs) {
-                return *el;
+/// Whether `to` can be reached from `from` following the `next` edges.
+fn reachable(next: &[Vec<usize>], from: usize, to: usize) -> bool {
+    let mut visited = vec![false; next.len()];
+    let mut stack = vec![from];
+    visited[from] = true;
+    while let Some(i) = stack.pop() {
+        if i == to {
+            return true;
+        }
+        for &j in &next[i] {
+            if !visited[j] {
+                visited[j] = true;
+                stack.push(j);
             }
-        } else {
-            return *el;
         }
     }
 
-    unreachable!()
+    false
 }
 
-fn parse_input() -> (HashMap<u8, HashSet<u8>>, Vec<Vec<u8>>) {
-    let mut rules: HashMap<u8, HashSet<u8>> = HashMap::new();
+fn join<P: Display>(pages: &[P], separator: &str) -> String {
+    pages
+        .iter()
+        .map(|page| page.to_string())
+        .collect::<Vec<_>>()
+        .join(separator)
+}
 
-    let split: Vec<&str> = read_input!(05).split("\n\n").take(2).collect();
-    for (el, req) in split[0].split("\n").map(|line| line.trim()).map(|line| {
-        let mut line_split = line.split("|");
-        let left = line_split.next().unwrap().parse::<u8>().unwrap();
-        let right = line_split.next().unwrap().parse::<u8>().unwrap();
-        (right, left)
-    }) {
-        if let Some(reqs) = rules.get_mut(&el) {
-            reqs.insert(req);
-        } else {
-            rules.insert(el, HashSet::from([req]));
+/// `1 -> 2 -> 3 -> 1`, each page required before the next.
+fn format_cycle<P: Display>(cycle: &[P]) -> String {
+    format!("{} -> {}", join(cycle, " -> "), cycle[0])
+}
+
+/// The corrected order of every update that breaks the rules.
+pub fn explain() -> Explanation {
+    let (rules, pages) = parse_input();
+    let mut explanation = Explanation::default();
+
+    explanation.section("Corrected updates");
+    for (i, page) in pages.iter().enumerate() {
+        if rules.is_ordered(page) {
+            continue;
         }
+
+        let detail = match rules.order(page) {
+            Ok(Sorted { pages, unique }) => format!(
+                "{} ({})",
+                join(&pages, ","),
+                if unique { "unique" } else { "not unique" }
+            ),
+            Err(cycle) => format!("no valid order, cycle {}", format_cycle(&cycle)),
+        };
+        explanation.entry(format!("update {}: {}", i + 1, join(page, ",")), detail);
+    }
+
+    explanation
+}
+
+pub fn repl(repl: &mut Repl) {
+    let (rules, updates) = parse_input();
+    let rules = Rc::new(rules);
+
+    let rules_valid = Rc::clone(&rules);
+    let rules_mid = Rc::clone(&rules);
+    let rules_order = Rc::clone(&rules);
+    let rules_before = Rc::clone(&rules);
+    let rules_cycles = Rc::clone(&rules);
+    let rules_redundant = Rc::clone(&rules);
+    let rules_unruled = Rc::clone(&rules);
+    repl.command(
+        "valid",
+        "valid <pages>\n    check whether a comma separated update is correctly ordered",
+        move |args| {
+            let page = parse_update(args)?;
+            let valid = rules_valid.is_ordered(&page);
+            Ok(String::from(if valid { "valid" } else { "invalid" }))
+        },
+    )
+    .command(
+        "mid",
+        "mid <pages>\n    middle page of the update once it is correctly ordered",
+        move |args| {
+            let sorted = order_update(&rules_mid, &parse_update(args)?)?;
+            Ok(sorted.pages[sorted.pages.len() / 2].to_string())
+        },
+    )
+    .command(
+        "order",
+        "order <pages>\n    the update correctly ordered and whether no other order is correct",
+        move |args| {
+            let sorted = order_update(&rules_order, &parse_update(args)?)?;
+            Ok(format!(
+                "{} ({})",
+                join(&sorted.pages, ","),
+                if sorted.unique {
+                    "unique"
+                } else {
+                    "not unique"
+                }
+            ))
+        },
+    )
+    .command(
+        "before",
+        "before <page>\n    pages that have to be printed before the page",
+        move |args| {
+            let page: Page = arg(args, 0, "page")?;
+            let mut reqs: Vec<Page> = rules_before
+                .before(page)
+                .map(|reqs| reqs.iter().cloned().collect())
+                .unwrap_or_default();
+            reqs.sort();
+            Ok(join(&reqs, ","))
+        },
+    )
+    .command(
+        "cycles",
+        "cycles\n    groups of pages whose rules form cycles, one group per line",
+        move |_| {
+            let cycles = rules_cycles.cycles();
+            if cycles.is_empty() {
+                return Ok(String::from("no cycles"));
+            }
+            Ok(cycles
+                .iter()
+                .map(|cycle| join(cycle, ","))
+                .collect::<Vec<_>>()
+                .join("\n"))
+        },
+    )
+    .command(
+        "redundant",
+        "redundant\n    rules that follow from other rules",
+        move |_| {
+            let redundant = rules_redundant.redundant();
+            let rules: Vec<String> = redundant.iter().map(|(a, b)| format!("{}|{}", a, b)).collect();
+            Ok(format!(
+                "{} of {} rules: {}",
+                redundant.len(),
+                rules_redundant.pairs().len(),
+                rules.join(" ")
+            ))
+        },
+    )
+    .command(
+        "unruled",
+        "unruled\n    pages of the updates that are in no rule",
+        move |_| {
+            let unruled = rules_unruled.unruled(&updates);
+            if unruled.is_empty() {
+                return Ok(String::from("every page is in a rule"));
+            }
+            Ok(join(&unruled, ","))
+        },
+    )
+    .command(
+        "dot",
+        "dot [file]\n    the rules as a Graphviz graph, redundant rules dashed and pages on cycles red",
+        move |args| {
+            let dot = rules.to_dot();
+            match args.first() {
+                Some(file) => fs::write(file, dot)
+                    .map(|_| format!("Wrote {}", file))
+                    .map_err(|e| format!("Could not write {}: {}", file, e)),
+                None => Ok(dot.trim_end().to_string()),
+            }
+        },
+    );
+}
+
+fn order_update(rules: &Rules<Page>, page: &[Page]) -> Result<Sorted<Page>, String> {
+    if page.is_empty() {
+        return Err(String::from("The update has no pages"));
     }
+    rules
+        .order(page)
+        .map_err(|cycle| format!("No valid order, cycle {}", format_cycle(&cycle)))
+}
+
+fn parse_update(args: &[&str]) -> Result<Vec<Page>, String> {
+    let pages: String = arg(args, 0, "pages")?;
+    pages
+        .split(",")
+        .map(|el| {
+            el.parse::<Page>()
+                .map_err(|_| format!("Not a valid page: {}", el))
+        })
+        .collect()
+}
+
+fn parse_input() -> (Rules<Page>, Vec<Vec<Page>>) {
+    let split: Vec<&str> = read_input!(05).split("\n\n").take(2).collect();
+    let rules = Rules::new(split[0].split("\n").map(|line| line.trim()).map(|line| {
+        let mut line_split = line.split("|");
+        let left = line_split.next().unwrap().parse::<Page>().unwrap();
+        let right = line_split.next().unwrap().parse::<Page>().unwrap();
+        (left, right)
+    }));
 
     let pages = split[1]
         .split("\n")
@@ -101,10 +529,96 @@ fn parse_input() -> (HashMap<u8, HashSet<u8>>, Vec<Vec<u8>>) {
         .filter(|line| !line.is_empty())
         .map(|line| {
             line.split(",")
-                .map(|el| el.parse::<u8>().unwrap())
+                .map(|el| el.parse::<Page>().unwrap())
                 .collect()
         })
         .collect();
 
     (rules, pages)
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn order_test() {
+        let rules = Rules::new([
+            (47, 53),
+            (97, 13),
+            (97, 61),
+            (97, 47),
+            (75, 29),
+            (61, 13),
+            (75, 53),
+            (29, 13),
+            (97, 29),
+            (53, 29),
+            (61, 53),
+            (97, 53),
+            (61, 29),
+            (47, 13),
+            (75, 47),
+            (97, 75),
+            (47, 61),
+            (75, 61),
+            (47, 29),
+            (75, 13),
+            (53, 13),
+        ]);
+        assert!(rules.is_ordered(&[75, 47, 61, 53, 29]));
+        assert!(!rules.is_ordered(&[97, 13, 75, 29, 47]));
+        assert_eq!(
+            Ok(Sorted {
+                pages: vec![97, 75, 47, 29, 13],
+                unique: true
+            }),
+            rules.order(&[97, 13, 75, 29, 47])
+        );
+
+        // 75 and 1000 are not related, the given order is kept
+        assert_eq!(
+            Ok(Sorted {
+                pages: vec![1000, 75, 13],
+                unique: false
+            }),
+            rules.order(&[1000, 13, 75])
+        );
+    }
+
+    #[test]
+    fn cycle_test() {
+        let rules = Rules::new([("a", "b"), ("b", "c"), ("c", "a"), ("x", "a")]);
+        assert_eq!(Err(vec!["a", "b", "c"]), rules.order(&["b", "x", "c", "a"]));
+        assert_eq!(
+            Ok(Sorted {
+                pages: vec!["c", "x", "a"],
+                unique: false
+            }),
+            rules.order(&["a", "c", "x"])
+        );
+    }
+
+    #[test]
+    fn analysis_test() {
+        let rules = Rules::new([
+            (1, 2),
+            (2, 3),
+            (1, 3),
+            (3, 4),
+            (5, 6),
+            (6, 7),
+            (7, 5),
+            (5, 7),
+        ]);
+        assert_eq!(vec![vec![5, 6, 7]], rules.cycles());
+        // 5|7 follows from 5|6 and 6|7
+        assert_eq!(vec![(1, 3), (5, 7)], rules.redundant());
+        assert_eq!(vec![8, 9], rules.unruled(&[vec![1, 9, 2], vec![8, 4, 9]]));
+
+        let dot = rules.to_dot();
+        assert!(dot.starts_with("digraph rules {\n    \"5\" [color=red];\n"));
+        assert!(dot.contains("    \"1\" -> \"2\";\n    \"1\" -> \"3\" [style=dashed];\n"));
+        assert_eq!(8, dot.matches("->").count());
+    }
+}
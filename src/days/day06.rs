@@ -1,12 +1,15 @@
 use std::hash::{Hash, Hasher};
 
-use hashbrown::HashSet;
-use rayon::iter::ParallelIterator;
-use rayon::prelude::IntoParallelIterator;
+#[cfg(feature = "parallel")]
+use rayon::iter::{IntoParallelIterator, ParallelIterator};
 
-use crate::{read_input, Solution, SolutionPair};
-
-static DIM: usize = 130;
+use crate::etc::bench::Bench;
+use crate::etc::validate::{grid_dim, Checks};
+use crate::etc::visualize::{Colour, Frame, Frames};
+use crate::utils::hash::FastSet;
+use crate::utils::intervals::SortedSet;
+use crate::utils::random::Rng;
+use crate::{debug, info, read_input, trace, Solution, SolutionPair};
 
 pub fn solve() -> SolutionPair {
     let (start_b, mut grid_b) = parse_input();
@@ -17,6 +20,18 @@ pub fn solve() -> SolutionPair {
     (Solution::from(sol1), Solution::from(sol2))
 }
 
+pub fn bench(bench: &mut Bench) {
+    bench.run("parse", parse_input);
+    let (start, grid) = parse_input();
+    bench.run("part1", || part1(start, &grid));
+    let (_, visited) = part1(start, &grid);
+    bench.run_with(
+        "part2",
+        || grid.clone(),
+        |mut grid| part2(start, &visited, &mut grid),
+    );
+}
+
 #[derive(Copy, Clone, Debug, PartialEq, Eq)]
 struct Position(usize, usize);
 
@@ -45,8 +60,8 @@ impl Position {
 
 impl Hash for Position {
     fn hash<H: Hasher>(&self, state: &mut H) {
-        // works for indexes up to u8::MAX - for the given input, max index is 129
-        state.write_usize((self.0 << 8) | self.1)
+        // works for indexes up to u32::MAX, far beyond any map that fits in memory
+        state.write_usize((self.0 << 32) | self.1)
     }
 }
 
@@ -71,13 +86,13 @@ impl Direction {
 
 #[derive(Debug, Clone)]
 struct Grid {
-    pub rows: Vec<Vec<usize>>,
-    pub cols: Vec<Vec<usize>>,
+    pub rows: Vec<SortedSet<usize>>,
+    pub cols: Vec<SortedSet<usize>>,
     pub dim: Position,
 }
 
-fn part1(mut pos: Position, grid: &Grid) -> (usize, HashSet<Position>) {
-    let mut visited = HashSet::new();
+fn part1(mut pos: Position, grid: &Grid) -> (usize, FastSet<Position>) {
+    let mut visited = FastSet::default();
     visited.insert(pos);
     let mut dir = Direction::UP;
     loop {
@@ -94,147 +109,83 @@ fn part1(mut pos: Position, grid: &Grid) -> (usize, HashSet<Position>) {
             },
         };
 
+        let segment_start = pos;
         loop {
             let next = if let Some(next) = pos.move_in_dir(dir) {
                 next
             } else {
                 break;
             };
-            if stop.0 > 0 && stop.1 > 0 && next == Position(stop.0 as usize, stop.1 as usize) {
+            if stop.0 >= 0 && stop.1 >= 0 && next == Position(stop.0 as usize, stop.1 as usize) {
                 break;
             }
 
             pos = next;
             visited.insert(pos);
         }
+        trace!("segment {:?} -> {:?} moving {:?}", segment_start, pos, dir);
 
         if to_break {
+            debug!("guard leaves the map at {:?} moving {:?}", pos, dir);
             break;
         }
 
         dir = dir.rotate();
     }
 
+    info!("guard visited {} positions", visited.len());
     (visited.len(), visited)
 }
 
-fn part2(start: Position, visited: &HashSet<Position>, grid: &mut Grid) -> usize {
+fn part2(start: Position, visited: &FastSet<Position>, grid: &mut Grid) -> usize {
     let visited: Vec<Position> = Vec::from_iter(visited.iter().cloned());
+    #[cfg(feature = "parallel")]
+    let visited = visited.into_par_iter();
+    #[cfg(not(feature = "parallel"))]
+    let visited = visited.into_iter();
+
     visited
-        .into_par_iter()
         .filter(|&pos| pos != start)
         .filter(|&pos| is_stuck(start, pos, &grid))
         .count()
 }
 
 #[allow(dead_code)]
-fn part2_clone(start: Position, visited: &HashSet<Position>, grid: &mut Grid) -> usize {
+fn part2_clone(start: Position, visited: &FastSet<Position>, grid: &mut Grid) -> usize {
     let visited: Vec<Position> = Vec::from_iter(visited.iter().cloned());
+    #[cfg(feature = "parallel")]
+    let visited = visited.into_par_iter();
+    #[cfg(not(feature = "parallel"))]
+    let visited = visited.into_iter();
+
     visited
-        .into_par_iter()
         .filter(|&pos| pos != start)
         .filter(move |&Position(i, j)| {
             let mut grid = grid.clone();
-            let ins_i = insert(j, &mut grid.rows[i]);
-            let ins_j = insert(i, &mut grid.cols[j]);
+            grid.rows[i].insert(j);
+            grid.cols[j].insert(i);
 
             let stuck = is_stuck_clone(start, &grid);
 
-            grid.rows[i].remove(ins_i);
-            grid.cols[j].remove(ins_j);
+            grid.rows[i].remove(j);
+            grid.cols[j].remove(i);
 
             stuck
         })
         .count()
 }
 
-fn insert(target: usize, vals: &mut Vec<usize>) -> usize {
-    match bisection(target, vals) {
-        Bisection::EMPTY | Bisection::RIGHT => {
-            vals.push(target);
-            vals.len() - 1
-        }
-        Bisection::LEFT => {
-            vals.insert(0, target);
-            0
-        }
-        Bisection::IN(idx) => {
-            vals.insert(idx + 1, target);
-            idx + 1
-        }
-    }
-}
-
 fn find_obstacle(pos: Position, dir: Direction, grid: &Grid) -> Option<usize> {
     match dir {
-        Direction::UP => {
-            let col = &grid.cols[pos.1];
-            match bisection(pos.0, col) {
-                Bisection::LEFT | Bisection::EMPTY => None,
-                Bisection::RIGHT => Some(*col.last().unwrap()),
-                Bisection::IN(i) => Some(col[i]),
-            }
-        }
-        Direction::RIGHT => {
-            let row = &grid.rows[pos.0];
-            match bisection(pos.1, row) {
-                Bisection::EMPTY | Bisection::RIGHT => None,
-                Bisection::LEFT => Some(row[0]),
-                Bisection::IN(i) => Some(row[i + 1]),
-            }
-        }
-        Direction::DOWN => {
-            let col = &grid.cols[pos.1];
-            match bisection(pos.0, col) {
-                Bisection::RIGHT | Bisection::EMPTY => None,
-                Bisection::LEFT => Some(col[0]),
-                Bisection::IN(i) => Some(col[i + 1]),
-            }
-        }
-        Direction::LEFT => {
-            let row = &grid.rows[pos.0];
-            match bisection(pos.1, row) {
-                Bisection::EMPTY | Bisection::LEFT => None,
-                Bisection::RIGHT => Some(*row.last().unwrap()),
-                Bisection::IN(i) => Some(row[i]),
-            }
-        }
+        Direction::UP => grid.cols[pos.1].predecessor(pos.0),
+        Direction::RIGHT => grid.rows[pos.0].successor(pos.1),
+        Direction::DOWN => grid.cols[pos.1].successor(pos.0),
+        Direction::LEFT => grid.rows[pos.0].predecessor(pos.1),
     }
 }
 
-#[derive(Clone, Copy, Debug, PartialEq)]
-enum Bisection {
-    EMPTY,
-    LEFT,
-    RIGHT,
-    IN(usize),
-}
-
-fn bisection(target: usize, vals: &[usize]) -> Bisection {
-    if vals.is_empty() {
-        return Bisection::EMPTY;
-    } else if target < vals[0] {
-        return Bisection::LEFT;
-    } else if target > *vals.last().unwrap() {
-        return Bisection::RIGHT;
-    }
-
-    let (mut left, mut right) = (0, vals.len());
-    while right - left > 1 {
-        let mid = (right + left) / 2;
-        let val = vals[mid];
-        if val <= target {
-            left = mid;
-        } else {
-            right = mid;
-        }
-    }
-
-    Bisection::IN(left)
-}
-
 fn is_stuck(mut pos: Position, obstacle: Position, grid: &Grid) -> bool {
-    let mut visited = HashSet::new();
+    let mut visited = FastSet::default();
     let mut dir = Direction::UP;
     loop {
         pos = match find_obstacle(pos, dir, &grid) {
@@ -316,7 +267,7 @@ fn is_stuck(mut pos: Position, obstacle: Position, grid: &Grid) -> bool {
 }
 
 fn is_stuck_clone(mut pos: Position, grid: &Grid) -> bool {
-    let mut visited = HashSet::new();
+    let mut visited = FastSet::default();
     let mut dir = Direction::UP;
     loop {
         pos = match find_obstacle(pos, dir, &grid) {
@@ -342,25 +293,111 @@ fn is_stuck_clone(mut pos: Position, grid: &Grid) -> bool {
     }
 }
 
+/// Guard walk, one frame per straight segment until the guard leaves the map or loops.
+pub fn visualize() -> Frames {
+    let (mut pos, grid) = parse_input();
+    let mut frame = Frame::new(String::new(), grid.dim.0, grid.dim.1);
+    for (i, row) in grid.rows.iter().enumerate() {
+        for &j in row.iter() {
+            frame.set(i, j, '#', Colour::White);
+        }
+    }
+
+    let mut dir = Direction::UP;
+    let mut turns = FastSet::default();
+    let mut visited = 1;
+    let mut segment = 0;
+    let mut done = false;
+    frame.set(pos.0, pos.1, 'X', Colour::Yellow);
+
+    Box::new(std::iter::from_fn(move || {
+        if done {
+            return None;
+        }
+
+        loop {
+            let next = match pos.move_in_dir(dir) {
+                Some(next) if next.0 < grid.dim.0 && next.1 < grid.dim.1 => next,
+                _ => {
+                    done = true;
+                    break;
+                }
+            };
+
+            if grid.rows[next.0].contains(next.1) {
+                dir = dir.rotate();
+                done = !turns.insert((pos, dir));
+                break;
+            }
+
+            pos = next;
+            if frame.cells[pos.0][pos.1].0 != 'X' {
+                visited += 1;
+            }
+            frame.set(pos.0, pos.1, 'X', Colour::Yellow);
+        }
+
+        segment += 1;
+        let mut out = frame.clone();
+        out.title = format!("Day 06 - segment {}, {} visited", segment, visited);
+        let guard = match dir {
+            Direction::UP => '^',
+            Direction::RIGHT => '>',
+            Direction::DOWN => 'v',
+            Direction::LEFT => '<',
+        };
+        out.set(pos.0, pos.1, guard, Colour::Red);
+
+        Some(out)
+    }))
+}
+
+/// Validate
+///
+/// The obstacle lists are sized from the first row, so the map has to be rectangular.
+pub fn validate(input: &str, checks: &mut Checks) {
+    let dim = grid_dim(input);
+    checks.check(
+        "map is rectangular",
+        dim.is_some(),
+        match dim {
+            Some((rows, cols)) => format!("{}x{}", rows, cols),
+            None => "rows of different lengths".to_string(),
+        },
+    );
+
+    let guards = input.matches('^').count();
+    checks.check(
+        "exactly one guard, facing up",
+        guards == 1,
+        format!("{} guards", guards),
+    );
+    let unknown = input.chars().filter(|c| !".#^\r\n".contains(*c)).count();
+    checks.check(
+        "map only contains '.', '#' and '^'",
+        unknown == 0,
+        format!("{} other characters", unknown),
+    );
+}
+
 fn parse_input() -> (Position, Grid) {
-    let mut start = Position(0, 0);
-    let mut rows = vec![Vec::with_capacity(DIM); DIM];
-    let mut cols = vec![Vec::with_capacity(DIM); DIM];
-    let (mut dim_i, mut dim_j) = (0, 0);
-    for (i, line) in read_input!(06)
+    let lines: Vec<&str> = read_input!(06)
         .trim()
         .split("\n")
         .map(|line| line.trim())
         .filter(|line| !line.is_empty())
-        .enumerate()
-    {
-        dim_i = i;
-        dim_j = line.len();
+        .collect();
+    let dim = Position(lines.len(), lines.first().map_or(0, |line| line.len()));
+
+    let mut start = Position(0, 0);
+    let mut rows = vec![SortedSet::with_capacity(dim.1); dim.0];
+    let mut cols = vec![SortedSet::with_capacity(dim.0); dim.1];
+    for (i, line) in lines.iter().enumerate() {
         for (j, c) in line.chars().enumerate() {
             match c {
                 '#' => {
-                    rows[i].push(j);
-                    cols[j].push(i);
+                    rows[i].insert(j);
+                    cols[j].insert(i);
                 }
                 '^' => {
                     start = Position(i, j);
@@ -370,25 +407,55 @@ fn parse_input() -> (Position, Grid) {
         }
     }
 
-    let dim = Position(dim_i + 1, dim_j);
     (start, Grid { rows, cols, dim })
 }
 
-#[cfg(test)]
-mod test {
-    use super::*;
-
-    #[test]
-    fn bisection_test() {
-        let vals = vec![1, 5, 10, 20, 100];
-        assert_eq!(Bisection::EMPTY, bisection(1, &vec![]));
-        assert_eq!(Bisection::LEFT, bisection(0, &vals));
-        assert_eq!(Bisection::IN(0), bisection(3, &vals));
-        assert_eq!(Bisection::IN(3), bisection(20, &vals));
-        assert_eq!(Bisection::IN(3), bisection(26, &vals));
-        assert_eq!(Bisection::IN(4), bisection(100, &vals));
-        assert_eq!(Bisection::RIGHT, bisection(1234, &vals));
-        assert_eq!(Bisection::RIGHT, bisection(4, &vec![1]));
-        assert_eq!(Bisection::RIGHT, bisection(6, &vec![1, 4]));
+/// Square `size x size` map with roughly 4% obstacles and the guard facing up. Maps where the
+/// guard is trapped in a loop from the start are drawn again, as the puzzle has it leave.
+pub fn generate(size: usize, rng: &mut Rng) -> String {
+    let grid = loop {
+        let mut grid = vec![vec![b'.'; size]; size];
+        for row in grid.iter_mut() {
+            for c in row.iter_mut() {
+                if rng.chance(1, 25) {
+                    *c = b'#';
+                }
+            }
+        }
+
+        let start = Position(rng.index(size), rng.index(size));
+        grid[start.0][start.1] = b'^';
+        if leaves_map(&grid, start) {
+            break grid;
+        }
+    };
+
+    let mut input = String::with_capacity(size * (size + 1));
+    for row in grid {
+        input.push_str(std::str::from_utf8(&row).unwrap());
+        input.push('\n');
+    }
+
+    input
+}
+
+/// Whether the guard walks off the generated map instead of running into a loop.
+fn leaves_map(grid: &[Vec<u8>], mut pos: Position) -> bool {
+    let mut dir = Direction::UP;
+    let mut turns = FastSet::default();
+    loop {
+        let next = match pos.move_in_dir(dir) {
+            Some(next) if next.0 < grid.len() && next.1 < grid.len() => next,
+            _ => return true,
+        };
+
+        if grid[next.0][next.1] == b'#' {
+            dir = dir.rotate();
+            if !turns.insert((pos, dir)) {
+                return false;
+            }
+        } else {
+            pos = next;
+        }
     }
 }
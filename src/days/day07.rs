@@ -1,6 +1,12 @@
-use rayon::iter::ParallelIterator;
-use rayon::prelude::IntoParallelRefIterator;
+#[cfg(feature = "parallel")]
+use rayon::iter::{IntoParallelRefIterator, ParallelIterator};
 
+use std::io;
+
+use crate::etc::bench::Bench;
+use crate::etc::explain::Explanation;
+use crate::utils::file::LineReader;
+use crate::utils::random::Rng;
 use crate::{read_input, Solution, SolutionPair};
 
 pub fn solve() -> SolutionPair {
@@ -12,9 +18,36 @@ pub fn solve() -> SolutionPair {
     (Solution::from(sol1), Solution::from(sol2))
 }
 
+/// Checks every equation as it is read, without keeping any of them.
+pub fn solve_stream(lines: &mut LineReader) -> io::Result<SolutionPair> {
+    let (mut sol1, mut sol2) = (0u64, 0u64);
+    lines.for_each(|line| {
+        let (target, vals) = parse_equation(line);
+        if check_recursive_1(target, 0, &vals) {
+            sol1 += target;
+        }
+        if check_recursive_2(target, 0, &vals) {
+            sol2 += target;
+        }
+    })?;
+
+    Ok((Solution::from(sol1), Solution::from(sol2)))
+}
+
+pub fn bench(bench: &mut Bench) {
+    bench.run("parse", parse_input);
+    let input = parse_input();
+    bench.run("part1", || part1(&input));
+    bench.run("part2", || part2(&input));
+}
+
 fn part1(input: &Vec<(u64, Vec<u64>)>) -> u64 {
+    #[cfg(feature = "parallel")]
+    let input = input.par_iter();
+    #[cfg(not(feature = "parallel"))]
+    let input = input.iter();
+
     input
-        .par_iter()
         .filter(|(target, vals)| check_recursive_1(*target, 0, vals))
         .map(|(target, _)| target)
         .sum()
@@ -32,8 +65,12 @@ fn check_recursive_1(target: u64, cur: u64, vals: &[u64]) -> bool {
 }
 
 fn part2(input: &Vec<(u64, Vec<u64>)>) -> u64 {
+    #[cfg(feature = "parallel")]
+    let input = input.par_iter();
+    #[cfg(not(feature = "parallel"))]
+    let input = input.iter();
+
     input
-        .par_iter()
         .filter(|(target, vals)| check_recursive_2(*target, 0, vals))
         .map(|(target, _)| target)
         .sum()
@@ -57,26 +94,139 @@ fn check_recursive_2(target: u64, cur: u64, vals: &[u64]) -> bool {
         || check_recursive_2(target, concat(cur, vals[0]), &vals[1..])
 }
 
+/// Operators of part 1 for every solvable equation, and of part 2 for the equations that can
+/// only be solved with concatenation.
+pub fn explain() -> Explanation {
+    let input = parse_input();
+    let mut explanation = Explanation::default();
+
+    explanation.section("Part 1 operators");
+    for (target, vals) in &input {
+        if let Some(ops) = find_operators(*target, vals, &['+', '*']) {
+            explanation.entry(target, format_equation(vals, &ops));
+        }
+    }
+
+    explanation.section("Part 2 operators (needing ||)");
+    for (target, vals) in &input {
+        if find_operators(*target, vals, &['+', '*']).is_some() {
+            continue;
+        }
+        if let Some(ops) = find_operators(*target, vals, &['+', '*', '|']) {
+            explanation.entry(target, format_equation(vals, &ops));
+        }
+    }
+
+    explanation
+}
+
+/// Operators (`+`, `*` or `|` for concatenation) that evaluate `vals` left to right to `target`.
+fn find_operators(target: u64, vals: &[u64], allowed: &[char]) -> Option<Vec<char>> {
+    fn search(target: u64, cur: u64, vals: &[u64], allowed: &[char], ops: &mut Vec<char>) -> bool {
+        if vals.is_empty() {
+            return target == cur;
+        } else if cur > target {
+            return false;
+        }
+
+        for &op in allowed {
+            let next = match op {
+                '+' => cur + vals[0],
+                '*' => cur * vals[0],
+                _ => concat(cur, vals[0]),
+            };
+            ops.push(op);
+            if search(target, next, &vals[1..], allowed, ops) {
+                return true;
+            }
+            ops.pop();
+        }
+
+        false
+    }
+
+    let mut ops = Vec::with_capacity(vals.len());
+    search(target, vals[0], &vals[1..], allowed, &mut ops).then_some(ops)
+}
+
+fn format_equation(vals: &[u64], ops: &[char]) -> String {
+    let mut equation = vals[0].to_string();
+    for (op, val) in ops.iter().zip(&vals[1..]) {
+        let op = match op {
+            '+' => "+",
+            '*' => "*",
+            _ => "||",
+        };
+        equation.push_str(&format!(" {} {}", op, val));
+    }
+    equation
+}
+
+// digits of the longest concatenation that fits in a u64
+const MAX_DIGITS: usize = 19;
+
 fn concat(lhs: u64, rhs: u64) -> u64 {
     let rhs_digits = rhs.ilog10() + 1;
     lhs * 10u64.pow(rhs_digits) + rhs
 }
 
 fn parse_input() -> Vec<(u64, Vec<u64>)> {
-    read_input!(07)
+    let mut equations = Vec::new();
+    LineReader::new(read_input!(07).as_bytes())
+        .for_each(|line| equations.push(parse_equation(line)))
+        .unwrap();
+
+    equations
+}
+
+fn parse_equation(line: &str) -> (u64, Vec<u64>) {
+    let mut split = line.split(":");
+    let target = split.next().unwrap().parse::<u64>().unwrap();
+    let vals = split
+        .next()
+        .unwrap()
         .trim()
-        .split("\n")
-        .map(|line| {
-            let mut split = line.trim().split(":");
-            let target = split.next().unwrap().parse::<u64>().unwrap();
-            let vals = split
-                .next()
-                .unwrap()
-                .trim()
-                .split(" ")
-                .map(|val| val.parse::<u64>().unwrap())
-                .collect();
-            (target, vals)
-        })
-        .collect()
+        .split(" ")
+        .map(|val| val.parse::<u64>().unwrap())
+        .collect();
+    (target, vals)
+}
+
+/// One equation per line with 3 to 12 operands. Half of the targets are built from random
+/// operators (so they are solvable), the rest are random.
+///
+/// Operands get fewer digits the more there are, so that concatenating all of them, which
+/// bounds every value the solvers compute, still fits in a `u64`.
+pub fn generate(size: usize, rng: &mut Rng) -> String {
+    let mut input = String::new();
+    for _ in 0..size {
+        let len = rng.range(3, 12) as usize;
+        let digits = (MAX_DIGITS / len).min(3) as u32;
+        let vals: Vec<u64> = (0..len)
+            .map(|_| rng.range(1, 10u64.pow(digits) - 1))
+            .collect();
+
+        let target = if rng.chance(1, 2) {
+            vals[1..].iter().fold(vals[0], |acc, val| {
+                let next = match rng.range(0, 2) {
+                    0 => acc + val,
+                    1 => acc * val,
+                    _ => concat(acc, *val),
+                };
+                // keep targets in the range of the original input, addition always fits
+                if next < 1_000_000_000_000_000 {
+                    next
+                } else {
+                    acc + val
+                }
+            })
+        } else {
+            rng.range(1, 1 << 40)
+        };
+
+        let vals: Vec<String> = vals.iter().map(|v| v.to_string()).collect();
+        input.push_str(&format!("{}: {}\n", target, vals.join(" ")));
+    }
+
+    input
 }
@@ -1,8 +1,8 @@
 use std::hash::{Hash, Hasher};
 use std::ops::{Add, Sub};
 
-use hashbrown::{HashMap, HashSet};
-
+use crate::etc::bench::Bench;
+use crate::utils::hash::{FastMap, FastSet};
 use crate::{read_input, Solution, SolutionPair};
 
 pub fn solve() -> SolutionPair {
@@ -14,6 +14,13 @@ pub fn solve() -> SolutionPair {
     (Solution::from(sol1), Solution::from(sol2))
 }
 
+pub fn bench(bench: &mut Bench) {
+    bench.run("parse", parse_input);
+    let (nodes, dim) = parse_input();
+    bench.run("part1", || part1(&nodes, dim));
+    bench.run("part2", || part2(&nodes, dim));
+}
+
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 struct Point(i32, i32);
 
@@ -45,8 +52,8 @@ impl Sub for Point {
     }
 }
 
-fn part1(nodes: &HashMap<u8, Vec<Point>>, dim: Point) -> usize {
-    let mut antinodes = HashSet::new();
+fn part1(nodes: &FastMap<u8, Vec<Point>>, dim: Point) -> usize {
+    let mut antinodes = FastSet::default();
     for ps in nodes.values() {
         for i in 0..ps.len() {
             for j in (i + 1)..ps.len() {
@@ -68,8 +75,8 @@ fn part1(nodes: &HashMap<u8, Vec<Point>>, dim: Point) -> usize {
     antinodes.len()
 }
 
-fn part2(nodes: &HashMap<u8, Vec<Point>>, dim: Point) -> usize {
-    let mut antinodes = HashSet::new();
+fn part2(nodes: &FastMap<u8, Vec<Point>>, dim: Point) -> usize {
+    let mut antinodes = FastSet::default();
     for ps in nodes.values() {
         for i in 0..ps.len() {
             for j in (i + 1)..ps.len() {
@@ -92,8 +99,8 @@ fn part2(nodes: &HashMap<u8, Vec<Point>>, dim: Point) -> usize {
     antinodes.len()
 }
 
-fn parse_input() -> (HashMap<u8, Vec<Point>>, Point) {
-    let mut antinodes: HashMap<u8, Vec<Point>> = HashMap::new();
+fn parse_input() -> (FastMap<u8, Vec<Point>>, Point) {
+    let mut antinodes: FastMap<u8, Vec<Point>> = FastMap::default();
     let (mut dim_i, mut dim_j) = (0, 0);
     read_input!(08)
         .trim()
@@ -1,44 +1,58 @@
-use std::collections::BinaryHeap;
-use std::fmt::Debug;
+use std::io;
+use std::ops::Range;
 
+use crate::etc::bench::Bench;
+use crate::utils::file::LineReader;
+use crate::utils::intervals::IntervalSet;
+use crate::utils::random::Rng;
 use crate::{read_input, Solution, SolutionPair};
 
-#[derive(Clone, Copy, Debug)]
-enum DiskType {
-    FILE(usize),
-    FREE,
+/// Disk Map
+///
+/// The blocks of every file, indexed by the file ID, and the free space between them.
+#[derive(Clone, Debug, Default)]
+struct DiskMap {
+    files: Vec<Range<usize>>,
+    free: IntervalSet,
+    end: usize,
+    digits: usize,
 }
 
-#[derive(Clone, Debug)]
-struct Disk {
-    disk_type: DiskType,
-    start: usize,
-    size: usize,
-}
-
-fn parse_input() -> Vec<Disk> {
-    let mut i_cur = 0;
-    read_input!(09)
-        .trim()
-        .bytes()
-        .map(|c| c - 48)
-        .enumerate()
-        .map(|(i, size)| {
-            let size = size as usize;
-            let disk_type = if i % 2 == 0 {
-                DiskType::FILE(i / 2)
+impl DiskMap {
+    /// Appends the next digits of the disk map, anything that is not a digit is skipped.
+    fn extend(&mut self, digits: &[u8]) {
+        for size in digits
+            .iter()
+            .filter(|c| c.is_ascii_digit())
+            .map(|c| (c - 48) as usize)
+        {
+            let blocks = self.end..self.end + size;
+            if self.digits.is_multiple_of(2) {
+                self.files.push(blocks);
             } else {
-                DiskType::FREE
-            };
-            let start = i_cur;
-            i_cur += size;
-            Disk {
-                disk_type,
-                start,
-                size,
+                self.free.insert(blocks);
             }
-        })
-        .collect()
+            self.end += size;
+            self.digits += 1;
+        }
+    }
+}
+
+fn parse_input() -> DiskMap {
+    let mut disk = DiskMap::default();
+    disk.extend(read_input!(09).as_bytes());
+    disk
+}
+
+/// Reads the disk map in chunks, as the whole input is a single line.
+pub fn solve_stream(lines: &mut LineReader) -> io::Result<SolutionPair> {
+    let mut disk = DiskMap::default();
+    lines.for_each_chunk(|chunk| disk.extend(chunk))?;
+
+    let sol1 = part1(disk.clone());
+    let sol2 = part2(disk);
+
+    Ok((Solution::from(sol1), Solution::from(sol2)))
 }
 
 fn sum_interval(start: usize, len: usize) -> usize {
@@ -49,151 +63,68 @@ fn sum_interval(start: usize, len: usize) -> usize {
     }
 }
 
-fn part1(mut disks: Vec<Disk>) -> usize {
+fn part1(mut disk: DiskMap) -> usize {
     let mut result = 0;
-    let mut i_disk = if disks.len() == 0 {
-        disks.len() - 2
-    } else {
-        disks.len() - 1
-    };
-    let mut i = 0;
-    while i <= i_disk {
-        let (disks1, disks2) = disks.split_at_mut(i + 1);
-        let disk = &mut disks1[i];
-        let checksum = match disk.disk_type {
-            DiskType::FILE(val) => {
-                let checksum = val * sum_interval(disk.start, disk.size);
-                i += 1;
-                checksum
-            }
-            DiskType::FREE => {
-                let fill_disk = &mut disks2[i_disk - i - 1];
-
-                let size = if disk.size > fill_disk.size {
-                    i_disk -= 2;
-                    fill_disk.size
-                } else {
-                    i += 1;
-                    disk.size
-                };
-
-                let val = match fill_disk.disk_type {
-                    DiskType::FILE(val) => val,
-                    DiskType::FREE => 0, // cannot happen
-                };
-
-                let checksum = val * sum_interval(disk.start, size);
-                disk.start += size;
-                disk.size -= size;
-                fill_disk.size -= size;
-
-                checksum
-            }
-        };
+    for (id, file) in disk.files.iter().enumerate().rev() {
+        // move blocks from the end of the file into the leftmost free space
+        let mut end = file.end;
+        while end > file.start {
+            let free = match disk.free.first_fit(1, file.start) {
+                Some(free) => free,
+                None => break,
+            };
+
+            let size = free.len().min(end - file.start);
+            disk.free.remove(free.start..free.start + size);
+            result += id * sum_interval(free.start, size);
+            end -= size;
+        }
 
-        result += checksum;
+        result += id * sum_interval(file.start, end - file.start);
     }
 
     result
 }
 
-fn part2(mut disks: Vec<Disk>) -> usize {
+fn part2(mut disk: DiskMap) -> usize {
     let mut result = 0;
-
-    let max_size = disks.iter().map(|disk| disk.size).max().unwrap_or(0);
-    let mut heaps = vec![BinaryHeap::new(); max_size + 1];
-    for disk in &disks {
-        match disk.disk_type {
-            DiskType::FILE(val) => {
-                heaps[disk.size].push((val * 2, disk.start));
-            }
-            DiskType::FREE => {}
-        }
-    }
-
-    let mut i = 0;
-    while i < disks.len() {
-        let (disks1, disks2) = disks.split_at_mut(i + 1);
-        let disk = &mut disks1[i];
-        let checksum = match disk.disk_type {
-            DiskType::FILE(val) => {
-                let checksum = val * sum_interval(disk.start, disk.size);
-                i += 1;
-                checksum
-            }
-            DiskType::FREE => {
-                let i_disk = match find_fill_disk(disk.start, disk.size, &mut heaps) {
-                    Some(i) => i,
-                    None => {
-                        i += 1;
-                        continue;
-                    }
-                };
-                let fill_disk = &mut disks2[i_disk - i - 1];
-
-                let size = fill_disk.size;
-                let val = match fill_disk.disk_type {
-                    DiskType::FILE(val) => val,
-                    DiskType::FREE => 0, // cannot happen
-                };
-
-                let checksum = val * sum_interval(disk.start, size);
-                disk.start += size;
-                disk.size -= size;
-                fill_disk.size = 0;
-
-                checksum
-            }
-        };
-
-        result += checksum;
+    for (id, file) in disk.files.iter().enumerate().rev() {
+        let size = file.len();
+        let start = disk.free.allocate(size, file.start).unwrap_or(file.start);
+        result += id * sum_interval(start, size);
     }
 
     result
 }
 
-fn find_fill_disk(
-    start: usize,
-    size: usize,
-    heaps: &mut [BinaryHeap<(usize, usize)>],
-) -> Option<usize> {
-    let mut disks = vec![None; size];
-    for size in 1..=size {
-        let heap = &mut heaps[size];
-        while let Some(disk) = heap.pop() {
-            if disk.1 > start {
-                disks[size - 1] = Some(disk);
-                break;
-            }
-        }
-    }
+pub fn solve() -> SolutionPair {
+    let disk = parse_input();
 
-    let mut index = None;
-    if let Some(max) = disks
-        .iter()
-        .filter(|disk| disk.is_some())
-        .map(|disk| disk.unwrap().0)
-        .max()
-    {
-        for size in 1..=size {
-            if let Some(disk) = disks[size - 1].take() {
-                if disk.0 == max {
-                    index = Some(disk.0);
-                } else {
-                    heaps[size].push(disk);
-                }
-            }
-        }
-    }
+    let sol1 = part1(disk.clone());
+    let sol2 = part2(disk);
 
-    index
+    (Solution::from(sol1), Solution::from(sol2))
 }
 
-pub fn solve() -> SolutionPair {
-    let disks = parse_input();
+pub fn bench(bench: &mut Bench) {
+    bench.run("parse", parse_input);
+    let disk = parse_input();
+    bench.run_with("part1", || disk.clone(), part1);
+    bench.run_with("part2", || disk.clone(), part2);
+}
 
-    let sol1 = part1(disks.clone());
-    let sol2 = part2(disks);
+/// Disk map of `size` digits, alternating file (1-9) and free (0-9) blocks.
+pub fn generate(size: usize, rng: &mut Rng) -> String {
+    let mut input = String::with_capacity(size + 1);
+    for i in 0..size {
+        let digit = if i % 2 == 0 {
+            rng.range(1, 9)
+        } else {
+            rng.range(0, 9)
+        };
+        input.push((b'0' + digit as u8) as char);
+    }
+    input.push('\n');
 
-    (Solution::from(sol1), Solution::from(sol2))
+    input
 }
@@ -1,7 +1,7 @@
 use std::hash::Hash;
 
-use hashbrown::HashSet;
-
+use crate::etc::bench::Bench;
+use crate::utils::hash::FastSet;
 use crate::{read_input, Solution, SolutionPair};
 
 pub fn solve() -> SolutionPair {
@@ -11,6 +11,13 @@ pub fn solve() -> SolutionPair {
     (Solution::from(sol1), Solution::from(sol2))
 }
 
+/// Both parts are solved together.
+pub fn bench(bench: &mut Bench) {
+    bench.run("parse", parse_input);
+    let map = parse_input();
+    bench.run("parts", || parts(&map));
+}
+
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
 struct Point(i32, i32);
 
@@ -31,7 +38,7 @@ fn parts(map: &Vec<Vec<i8>>) -> (usize, usize) {
 }
 
 fn count_trails(p: Point, map: &Vec<Vec<i8>>) -> (usize, usize) {
-    let mut nines = HashSet::new();
+    let mut nines = FastSet::default();
     let trails = count_trails_recursive(-1, p, map, &mut nines);
     (nines.len(), trails)
 }
@@ -40,7 +47,7 @@ fn count_trails_recursive(
     prev: i8,
     p: Point,
     map: &Vec<Vec<i8>>,
-    nines: &mut HashSet<Point>,
+    nines: &mut FastSet<Point>,
 ) -> usize {
     let cur = map[p.0 as usize][p.1 as usize];
     if cur != prev + 1 {
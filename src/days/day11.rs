@@ -1,32 +1,60 @@
-use hashbrown::HashMap;
-
+use crate::etc::bench::Bench;
+use crate::etc::params;
+use crate::utils::hash::FastMap;
+use crate::utils::random::Rng;
 use crate::{read_input, Solution, SolutionPair};
 
 pub fn solve() -> SolutionPair {
+    let params = Params::load();
     let stones = parse_input();
 
-    let sol1 = part1(&stones);
-    let sol2 = part2(&stones);
+    let sol1 = part1(&stones, &params);
+    let sol2 = part2(&stones, &params);
 
     (Solution::from(sol1), Solution::from(sol2))
 }
 
-fn part1(stones: &[u64]) -> usize {
-    get_count(25, stones)
+pub fn bench(bench: &mut Bench) {
+    let params = Params::load();
+    bench.run("parse", parse_input);
+    let stones = parse_input();
+    bench.run("part1", || part1(&stones, &params));
+    bench.run("part2", || part2(&stones, &params));
 }
 
-fn part2(stones: &[u64]) -> usize {
-    get_count(75, stones)
+/// Params
+///
+/// Number of blinks in each part.
+struct Params {
+    blinks1: usize,
+    blinks2: usize,
+}
+
+impl Params {
+    fn load() -> Self {
+        Self {
+            blinks1: params::get(11, "blinks1", 25),
+            blinks2: params::get(11, "blinks2", 75),
+        }
+    }
+}
+
+fn part1(stones: &[u64], params: &Params) -> usize {
+    get_count(params.blinks1, stones)
+}
+
+fn part2(stones: &[u64], params: &Params) -> usize {
+    get_count(params.blinks2, stones)
 }
 
 fn get_count(blinks: usize, stones: &[u64]) -> usize {
-    let mut cur_stones = HashMap::new();
+    let mut cur_stones = FastMap::default();
     for stone in stones {
         add_map(*stone, 1, &mut cur_stones);
     }
 
     for _ in 0..blinks {
-        let mut stones_new = HashMap::new();
+        let mut stones_new = FastMap::default();
         for (stone, count) in cur_stones {
             if stone == 0 {
                 add_map(1, count, &mut stones_new);
@@ -48,7 +76,7 @@ fn get_count(blinks: usize, stones: &[u64]) -> usize {
     cur_stones.values().sum()
 }
 
-fn add_map(key: u64, value: usize, map: &mut HashMap<u64, usize>) {
+fn add_map(key: u64, value: usize, map: &mut FastMap<u64, usize>) {
     if let Some(map_value) = map.get_mut(&key) {
         *map_value += value;
     } else {
@@ -63,3 +91,15 @@ fn parse_input() -> Vec<u64> {
         .map(|x| x.parse::<u64>().unwrap())
         .collect()
 }
+
+/// A single line of `size` stones with up to 7 digits each.
+pub fn generate(size: usize, rng: &mut Rng) -> String {
+    let stones: Vec<String> = (0..size)
+        .map(|_| {
+            let digits = rng.range(1, 7) as u32;
+            rng.range(0, 10u64.pow(digits) - 1).to_string()
+        })
+        .collect();
+
+    stones.join(" ") + "\n"
+}
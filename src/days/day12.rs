@@ -1,9 +1,12 @@
 use std::collections::VecDeque;
 use std::hash::{Hash, Hasher};
 
-use hashbrown::{HashMap, HashSet};
+#[cfg(feature = "parallel")]
 use rayon::iter::{IntoParallelRefIterator, ParallelIterator};
 
+use crate::etc::bench::Bench;
+use crate::etc::validate::{grid_dim, Checks};
+use crate::utils::hash::{FastMap, FastSet};
 use crate::{read_input, Solution, SolutionPair};
 
 static DIRS: [Dir; 4] = [Dir::UP, Dir::RIGHT, Dir::DOWN, Dir::LEFT];
@@ -17,6 +20,14 @@ pub fn solve() -> SolutionPair {
     (Solution::from(sol1), Solution::from(sol2))
 }
 
+pub fn bench(bench: &mut Bench) {
+    bench.run("parse", parse_input);
+    let grid = parse_input();
+    bench.run_with("part1", || grid.clone(), part1);
+    let (_, gardens) = part1(grid);
+    bench.run_with("part2", || gardens.clone(), part2);
+}
+
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 struct Point(i32, i32);
 
@@ -65,8 +76,18 @@ impl Dir {
     }
 }
 
-fn parse_input() -> HashMap<Point, u8> {
-    let mut grid = HashMap::new();
+/// Validate
+///
+/// `Point` packs columns into 8 bits.
+pub fn validate(input: &str, checks: &mut Checks) {
+    match grid_dim(input) {
+        Some((_, cols)) => checks.packed_hash(cols.saturating_sub(1)),
+        None => checks.check("map is rectangular", false, "rows of different lengths"),
+    }
+}
+
+fn parse_input() -> FastMap<Point, u8> {
+    let mut grid = FastMap::default();
     read_input!(12)
         .trim()
         .split("\n")
@@ -81,9 +102,9 @@ fn parse_input() -> HashMap<Point, u8> {
     grid
 }
 
-fn part1(mut grid: HashMap<Point, u8>) -> (usize, Vec<HashSet<Point>>) {
+fn part1(mut grid: FastMap<Point, u8>) -> (usize, Vec<FastSet<Point>>) {
     let mut result = 0;
-    let mut gardens: Vec<HashSet<Point>> = Vec::new();
+    let mut gardens: Vec<FastSet<Point>> = Vec::new();
 
     while !grid.is_empty() {
         let p = grid.keys().next().unwrap();
@@ -95,7 +116,7 @@ fn part1(mut grid: HashMap<Point, u8>) -> (usize, Vec<HashSet<Point>>) {
     (result, gardens)
 }
 
-fn get_garden_cost(garden: &HashSet<Point>) -> usize {
+fn get_garden_cost(garden: &FastSet<Point>) -> usize {
     let perimeter: usize = garden
         .iter()
         .map(|p| {
@@ -110,9 +131,9 @@ fn get_garden_cost(garden: &HashSet<Point>) -> usize {
     garden.len() * perimeter
 }
 
-fn search_garden(p: Point, grid: &mut HashMap<Point, u8>) -> HashSet<Point> {
+fn search_garden(p: Point, grid: &mut FastMap<Point, u8>) -> FastSet<Point> {
     let v = grid.remove(&p).unwrap();
-    let mut area = HashSet::new();
+    let mut area = FastSet::default();
     let mut queue = VecDeque::new();
     queue.push_back(p);
 
@@ -131,12 +152,17 @@ fn search_garden(p: Point, grid: &mut HashMap<Point, u8>) -> HashSet<Point> {
     area
 }
 
-fn part2(gardens: Vec<HashSet<Point>>) -> usize {
-    gardens.par_iter().map(get_sides_count).sum()
+fn part2(gardens: Vec<FastSet<Point>>) -> usize {
+    #[cfg(feature = "parallel")]
+    let gardens = gardens.par_iter();
+    #[cfg(not(feature = "parallel"))]
+    let gardens = gardens.iter();
+
+    gardens.map(get_sides_count).sum()
 }
 
-fn get_sides_count(garden: &HashSet<Point>) -> usize {
-    let mut sides = HashMap::new();
+fn get_sides_count(garden: &FastSet<Point>) -> usize {
+    let mut sides = FastMap::default();
     for p in garden {
         for dir in DIRS {
             let p1 = p.move_in(dir);
@@ -154,7 +180,7 @@ fn get_sides_count(garden: &HashSet<Point>) -> usize {
     garden.len() * sides_count
 }
 
-fn update_sides(p: Point, dir: Dir, sides: &mut HashMap<(Point, Dir), Point>) {
+fn update_sides(p: Point, dir: Dir, sides: &mut FastMap<(Point, Dir), Point>) {
     let pl = p.move_in(dir.rotate_left());
     let pl = sides.remove(&(pl, dir)).unwrap_or(p);
 
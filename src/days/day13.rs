@@ -1,18 +1,46 @@
+#[cfg(feature = "regex")]
 use regex::Regex;
 
+use crate::etc::bench::Bench;
+use crate::etc::explain::Explanation;
+use crate::etc::params;
 use crate::{read_input, Solution, SolutionPair};
 
 type Point = (i64, i64);
 
 pub fn solve() -> SolutionPair {
+    let params = Params::load();
     let machines = parse_intput();
 
     let sol1 = part1(&machines);
-    let sol2 = part2(&machines);
+    let sol2 = part2(&machines, &params);
 
     (Solution::from(sol1), Solution::from(sol2))
 }
 
+pub fn bench(bench: &mut Bench) {
+    let params = Params::load();
+    bench.run("parse", parse_intput);
+    let machines = parse_intput();
+    bench.run("part1", || part1(&machines));
+    bench.run("part2", || part2(&machines, &params));
+}
+
+/// Params
+///
+/// Offset added to the prize coordinates in part 2.
+struct Params {
+    offset: i64,
+}
+
+impl Params {
+    fn load() -> Self {
+        Self {
+            offset: params::get(13, "offset", 10000000000000),
+        }
+    }
+}
+
 fn part1(machines: &[(Point, Point, Point)]) -> i64 {
     machines
         .iter()
@@ -20,35 +48,55 @@ fn part1(machines: &[(Point, Point, Point)]) -> i64 {
         .sum()
 }
 
-fn part2(machines: &[(Point, Point, Point)]) -> i64 {
+fn part2(machines: &[(Point, Point, Point)], params: &Params) -> i64 {
     machines
         .iter()
-        .map(|(a, b, p)| (*a, *b, (p.0 + 10000000000000, p.1 + 10000000000000)))
+        .map(|(a, b, p)| (*a, *b, (p.0 + params.offset, p.1 + params.offset)))
         .map(|(a, b, p)| machine_price(a, b, p))
         .sum()
 }
 
+/// Button presses of every machine in both parts and the tokens they cost.
+pub fn explain() -> Explanation {
+    let params = Params::load();
+    let machines = parse_intput();
+    let mut explanation = Explanation::default();
+
+    for (part, offset) in [(1, 0), (2, params.offset)] {
+        explanation.section(format!("Part {} button presses", part));
+        for (i, (a, b, p)) in machines.iter().enumerate() {
+            let p = (p.0 + offset, p.1 + offset);
+            let detail = match machine_presses(*a, *b, p) {
+                Some((n, m)) => format!("A x{}, B x{} = {} tokens", n, m, 3 * n + m),
+                None => String::from("prize cannot be won"),
+            };
+            explanation.entry(format!("machine {}", i + 1), detail);
+        }
+    }
+
+    explanation
+}
+
 fn machine_price(a: Point, b: Point, p: Point) -> i64 {
+    match machine_presses(a, b, p) {
+        Some((n, m)) => 3 * n + m,
+        None => 0,
+    }
+}
+
+/// Presses of buttons `a` and `b` that reach the prize `p`, if there are any.
+fn machine_presses(a: Point, b: Point, p: Point) -> Option<(i64, i64)> {
     let left = a.1 * p.0 - a.0 * p.1;
     let right = a.1 * b.0 - a.0 * b.1;
 
     let m = left / right;
     let n = (p.0 - m * b.0) / a.0;
 
-    if n * a.0 + m * b.0 == p.0 && n * a.1 + m * b.1 == p.1 {
-        3 * n + m
-    } else {
-        0
-    }
+    (n * a.0 + m * b.0 == p.0 && n * a.1 + m * b.1 == p.1).then_some((n, m))
 }
 
 fn parse_intput() -> Vec<(Point, Point, Point)> {
-    let re = Regex::new(r"X.(\d+), Y.(\d+)").unwrap();
-    let points: Vec<Point> = re
-        .captures_iter(read_input!(13))
-        .map(|c| c.extract())
-        .map(|(_, [x, y])| (x.parse().unwrap(), y.parse().unwrap()))
-        .collect();
+    let points = parse_points();
 
     let mut machines = Vec::with_capacity(points.len() / 3);
     for i in 0..points.len() / 3 {
@@ -57,3 +105,28 @@ fn parse_intput() -> Vec<(Point, Point, Point)> {
 
     machines
 }
+
+#[cfg(feature = "regex")]
+fn parse_points() -> Vec<Point> {
+    let re = Regex::new(r"X.(\d+), Y.(\d+)").unwrap();
+    re.captures_iter(read_input!(13))
+        .map(|c| c.extract())
+        .map(|(_, [x, y])| (x.parse().unwrap(), y.parse().unwrap()))
+        .collect()
+}
+
+/// Every line with an `X` holds the two numbers of a button or prize.
+#[cfg(not(feature = "regex"))]
+fn parse_points() -> Vec<Point> {
+    read_input!(13)
+        .lines()
+        .filter(|line| line.contains('X'))
+        .map(|line| {
+            let mut numbers = line
+                .split(|c: char| !c.is_ascii_digit())
+                .filter(|number| !number.is_empty())
+                .map(|number| number.parse().unwrap());
+            (numbers.next().unwrap(), numbers.next().unwrap())
+        })
+        .collect()
+}
@@ -1,21 +1,34 @@
 use std::hash::{Hash, Hasher};
 
-use hashbrown::HashSet;
+#[cfg(feature = "regex")]
 use regex::Regex;
 
+use crate::etc::bench::Bench;
+use crate::etc::params;
+use crate::etc::validate::Checks;
+use crate::etc::visualize::{Colour, Frame, Frames};
+use crate::utils::hash::FastSet;
+use crate::utils::random::Rng;
 use crate::{read_input, Solution, SolutionPair};
 
-static DIM: Point = Point(101, 103);
-
 pub fn solve() -> SolutionPair {
+    let params = Params::load();
     let robots = parse_input();
 
-    let sol1 = part1(&robots);
-    let sol2 = part2(&robots);
+    let sol1 = part1(&robots, &params);
+    let sol2 = part2(&robots, &params);
 
     (Solution::from(sol1), Solution::from(sol2))
 }
 
+pub fn bench(bench: &mut Bench) {
+    let params = Params::load();
+    bench.run("parse", parse_input);
+    let robots = parse_input();
+    bench.run("part1", || part1(&robots, &params));
+    bench.run("part2", || part2(&robots, &params));
+}
+
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 struct Point(i64, i64);
 
@@ -25,35 +38,57 @@ impl Hash for Point {
     }
 }
 
-fn part1(robots: &[(Point, Point)]) -> usize {
+/// Params
+///
+/// Size of the floor and the number of seconds to simulate in part 1.
+struct Params {
+    dim: Point,
+    seconds: i64,
+}
+
+impl Params {
+    fn load() -> Self {
+        Self {
+            dim: Point(
+                params::get(14, "width", 101),
+                params::get(14, "height", 103),
+            ),
+            seconds: params::get(14, "seconds", 100),
+        }
+    }
+}
+
+fn part1(robots: &[(Point, Point)], params: &Params) -> usize {
+    let (dim, seconds) = (params.dim, params.seconds);
     let mut quadrants = [0, 0, 0, 0];
     robots
         .iter()
         .map(|robot| {
             Point(
-                ((robot.0 .0 + 100 * robot.1 .0) % DIM.0 + DIM.0) % DIM.0,
-                ((robot.0 .1 + 100 * robot.1 .1) % DIM.1 + DIM.1) % DIM.1,
+                ((robot.0 .0 + seconds * robot.1 .0) % dim.0 + dim.0) % dim.0,
+                ((robot.0 .1 + seconds * robot.1 .1) % dim.1 + dim.1) % dim.1,
             )
         })
-        .filter(|p| p.0 != DIM.0 / 2 && p.1 != DIM.1 / 2)
+        .filter(|p| p.0 != dim.0 / 2 && p.1 != dim.1 / 2)
         .for_each(|p| {
-            let xq = if p.0 < DIM.0 / 2 { 0 } else { 1 };
-            let yq = if p.1 < DIM.1 / 2 { 0 } else { 1 };
+            let xq = if p.0 < dim.0 / 2 { 0 } else { 1 };
+            let yq = if p.1 < dim.1 / 2 { 0 } else { 1 };
             quadrants[2 * yq + (xq as usize)] += 1;
         });
 
     quadrants.iter().product()
 }
 
-fn part2(robots: &[(Point, Point)]) -> usize {
+fn part2(robots: &[(Point, Point)], params: &Params) -> usize {
+    let dim = params.dim;
     let mut i = 0;
     loop {
-        let points: HashSet<Point> = robots
+        let points: FastSet<Point> = robots
             .iter()
             .map(|robot| {
                 Point(
-                    ((robot.0 .0 + i * robot.1 .0) % DIM.0 + DIM.0) % DIM.0,
-                    ((robot.0 .1 + i * robot.1 .1) % DIM.1 + DIM.1) % DIM.1,
+                    ((robot.0 .0 + i * robot.1 .0) % dim.0 + dim.0) % dim.0,
+                    ((robot.0 .1 + i * robot.1 .1) % dim.1 + dim.1) % dim.1,
                 )
             })
             .collect();
@@ -80,6 +115,73 @@ fn part2(robots: &[(Point, Point)]) -> usize {
     }
 }
 
+/// Robot positions, one frame per second until the positions repeat.
+pub fn visualize() -> Frames {
+    let dim = Params::load().dim;
+    let robots = parse_input();
+    Box::new((0..dim.0 * dim.1).map(move |i| {
+        let mut frame = Frame::new(
+            format!("Day 14 - second {}", i),
+            dim.1 as usize,
+            dim.0 as usize,
+        );
+        for robot in &robots {
+            let x = ((robot.0 .0 + i * robot.1 .0) % dim.0 + dim.0) % dim.0;
+            let y = ((robot.0 .1 + i * robot.1 .1) % dim.1 + dim.1) % dim.1;
+            frame.set(y as usize, x as usize, '#', Colour::Green);
+        }
+
+        frame
+    }))
+}
+
+/// Validate
+///
+/// Robots have to start on the `dim` sized floor, whose height also has to fit into the 8 bits
+/// `Point` packs it into.
+pub fn validate(input: &str, checks: &mut Checks) {
+    let dim = Params::load().dim;
+    let lines: Vec<&str> = input
+        .trim()
+        .lines()
+        .map(|line| line.trim())
+        .filter(|line| !line.is_empty())
+        .collect();
+    let robots: Vec<(i64, i64)> = lines
+        .iter()
+        .filter_map(|line| parse_robot(line))
+        .map(|(p, _)| (p.0, p.1))
+        .collect();
+
+    checks.check(
+        "every line is a `p=x,y v=dx,dy` robot",
+        robots.len() == lines.len(),
+        format!("{} of {} lines", robots.len(), lines.len()),
+    );
+    checks.check(
+        "floor has positive dimensions",
+        dim.0 > 0 && dim.1 > 0,
+        format!("{}x{}", dim.0, dim.1),
+    );
+    checks.check(
+        "at least one robot",
+        !robots.is_empty(),
+        format!("{} robots", robots.len()),
+    );
+
+    let outside = robots
+        .iter()
+        .filter(|&&(x, y)| x < 0 || x >= dim.0 || y < 0 || y >= dim.1)
+        .count();
+    checks.check(
+        format!("robots start inside the {}x{} floor", dim.0, dim.1),
+        outside == 0,
+        format!("{} robots outside", outside),
+    );
+    checks.packed_hash((dim.1 as usize).saturating_sub(1));
+}
+
+#[cfg(feature = "regex")]
 fn parse_input() -> Vec<(Point, Point)> {
     let re = Regex::new(r"p=(-?\d+),(-?\d+) v=(-?\d+),(-?\d+)").unwrap();
     re.captures_iter(read_input!(14))
@@ -92,3 +194,44 @@ fn parse_input() -> Vec<(Point, Point)> {
         })
         .collect()
 }
+
+#[cfg(not(feature = "regex"))]
+fn parse_input() -> Vec<(Point, Point)> {
+    read_input!(14)
+        .lines()
+        .map(|line| line.trim())
+        .filter(|line| !line.is_empty())
+        .map(|line| parse_robot(line).unwrap())
+        .collect()
+}
+
+/// A `p=x,y v=dx,dy` robot.
+fn parse_robot(line: &str) -> Option<(Point, Point)> {
+    let (p, v) = line.strip_prefix("p=")?.split_once(" v=")?;
+    let (px, py) = p.split_once(',')?;
+    let (vx, vy) = v.split_once(',')?;
+
+    Some((
+        Point(px.parse().ok()?, py.parse().ok()?),
+        Point(vx.parse().ok()?, vy.parse().ok()?),
+    ))
+}
+
+/// `size` robots on the `dim` sized floor with velocities up to 100 in each direction.
+pub fn generate(size: usize, rng: &mut Rng) -> String {
+    let dim = Params::load().dim;
+    let mut input = String::new();
+    for _ in 0..size {
+        let p = (
+            rng.range(0, dim.0 as u64 - 1),
+            rng.range(0, dim.1 as u64 - 1),
+        );
+        let v = (
+            rng.range(0, 200) as i64 - 100,
+            rng.range(0, 200) as i64 - 100,
+        );
+        input.push_str(&format!("p={},{} v={},{}\n", p.0, p.1, v.0, v.1));
+    }
+
+    input
+}
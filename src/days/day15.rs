@@ -1,10 +1,11 @@
 use std::hash::{Hash, Hasher};
 
-use hashbrown::HashMap;
-
+use crate::etc::bench::Bench;
+use crate::etc::visualize::{Colour, Frame, Frames};
+use crate::utils::hash::FastMap;
 use crate::{read_input, Solution, SolutionPair};
 
-type Grid = HashMap<Point, Object>;
+type Grid = FastMap<Point, Object>;
 
 pub fn solve() -> SolutionPair {
     let (start1, mut grid1, moves) = parse_input();
@@ -16,6 +17,22 @@ pub fn solve() -> SolutionPair {
     (Solution::from(sol1), Solution::from(sol2))
 }
 
+pub fn bench(bench: &mut Bench) {
+    bench.run("parse", parse_input);
+    let (start1, grid1, moves) = parse_input();
+    let (start2, grid2) = prepare_part2(start1, &grid1);
+    bench.run_with(
+        "part1",
+        || grid1.clone(),
+        |mut grid| part1(start1, &mut grid, &moves),
+    );
+    bench.run_with(
+        "part2",
+        || grid2.clone(),
+        |mut grid| part2(start2, &mut grid, &moves),
+    );
+}
+
 fn part1(mut pos: Point, grid: &mut Grid, moves: &Vec<Move>) -> i64 {
     moves.iter().for_each(|m| pos = do_move1(pos, *m, grid));
 
@@ -155,8 +172,42 @@ fn push_left_right(p: Point, m: Move, grid: &mut Grid) -> Result<(), ()> {
     Ok(())
 }
 
+/// Wide warehouse from part 2, one frame per robot move.
+pub fn visualize() -> Frames {
+    let (start, grid, moves) = parse_input();
+    let (mut pos, mut grid) = prepare_part2(start, &grid);
+    let height = grid.keys().map(|p| p.0).max().unwrap_or(0) as usize + 1;
+    let width = grid.keys().map(|p| p.1).max().unwrap_or(0) as usize + 2;
+
+    let mut frames = 0..=moves.len();
+    Box::new(std::iter::from_fn(move || {
+        let i = frames.next()?;
+        if i > 0 {
+            pos = do_move2(pos, moves[i - 1], &mut grid);
+        }
+
+        let mut frame = Frame::new(format!("Day 15 - move {}", i), height, width);
+        for (p, object) in &grid {
+            let (i, j) = (p.0 as usize, p.1 as usize);
+            match object {
+                Object::Wall => {
+                    frame.set(i, j, '#', Colour::White);
+                    frame.set(i, j + 1, '#', Colour::White);
+                }
+                Object::Box => {
+                    frame.set(i, j, '[', Colour::Yellow);
+                    frame.set(i, j + 1, ']', Colour::Yellow);
+                }
+            }
+        }
+        frame.set(pos.0 as usize, pos.1 as usize, '@', Colour::Red);
+
+        Some(frame)
+    }))
+}
+
 fn parse_input() -> (Point, Grid, Vec<Move>) {
-    let mut grid = HashMap::new();
+    let mut grid = FastMap::default();
     let mut moves = Vec::new();
     let mut start = Point(0, 0);
 
@@ -3,9 +3,11 @@ use std::collections::BinaryHeap;
 use std::hash::{Hash, Hasher};
 use std::rc::Rc;
 
-use hashbrown::{HashMap, HashSet};
-
-use crate::{read_input, Solution, SolutionPair};
+use crate::etc::bench::Bench;
+use crate::etc::validate::{border_gaps, grid_dim, Checks};
+use crate::etc::visualize::{Colour, Frame, Frames};
+use crate::utils::hash::{FastMap, FastSet};
+use crate::{debug, read_input, trace, Solution, SolutionPair};
 
 pub fn solve() -> SolutionPair {
     let (grid, start) = parse_input();
@@ -16,15 +18,29 @@ pub fn solve() -> SolutionPair {
     (Solution::from(sol1), Solution::from(sol2))
 }
 
+pub fn bench(bench: &mut Bench) {
+    bench.run("parse", parse_input);
+    let (grid, start) = parse_input();
+    bench.run("part1", || part1(start, &grid));
+    let opt = part1(start, &grid);
+    bench.run("part2", || part2(start, opt, &grid));
+}
+
 fn part1(start: Point, grid: &Vec<Vec<Object>>) -> u64 {
-    let mut seen = HashSet::new();
+    let mut seen = FastSet::default();
     let mut queue = BinaryHeap::with_capacity(grid.len() * grid[0].len());
     queue.push(State(0, start, Direction::Right));
     while !queue.is_empty() {
         let State(score, p, dir) = queue.pop().unwrap();
+        trace!("part1: popped score {}, queue size {}", score, queue.len());
 
         // Check if end
         if grid[p.0][p.1] == Object::End {
+            debug!(
+                "part1: end reached with score {}, {} states left in queue",
+                score,
+                queue.len()
+            );
             return score;
         }
 
@@ -50,16 +66,26 @@ fn part1(start: Point, grid: &Vec<Vec<Object>>) -> u64 {
 }
 
 fn part2(start: Point, opt: u64, grid: &Vec<Vec<Object>>) -> usize {
-    let mut seen: HashMap<(Point, Direction), u64> = HashMap::new();
+    get_optimal_tiles(start, opt, grid).len()
+}
+
+fn get_optimal_tiles(start: Point, opt: u64, grid: &Vec<Vec<Object>>) -> FastSet<Point> {
+    let mut seen: FastMap<(Point, Direction), u64> = FastMap::default();
     seen.insert((start, Direction::Right), 0);
-    let mut on_opt_path: HashSet<Point> = HashSet::new();
+    let mut on_opt_path: FastSet<Point> = FastSet::default();
     let mut queue = BinaryHeap::with_capacity(grid.len() * grid[0].len());
     queue.push(State2(0, start, Direction::Right, Rc::new(vec![start])));
     while !queue.is_empty() {
         let State2(score, p, dir, path) = queue.pop().unwrap();
+        trace!("part2: popped score {}, queue size {}", score, queue.len());
 
         // Check score
         if score > opt {
+            debug!(
+                "part2: score {} exceeds optimum, {} states left in queue",
+                score,
+                queue.len()
+            );
             break;
         }
 
@@ -100,7 +126,65 @@ fn part2(start: Point, opt: u64, grid: &Vec<Vec<Object>>) -> usize {
         }
     }
 
-    on_opt_path.len()
+    on_opt_path
+}
+
+/// The maze, followed by the same maze with all tiles on optimal paths highlighted.
+pub fn visualize() -> Frames {
+    let (grid, start) = parse_input();
+    let opt = part1(start, &grid);
+
+    let mut maze = Frame::new(String::from("Day 16 - maze"), grid.len(), grid[0].len());
+    for (i, row) in grid.iter().enumerate() {
+        for (j, object) in row.iter().enumerate() {
+            match object {
+                Object::Wall => maze.set(i, j, '#', Colour::White),
+                Object::Start => maze.set(i, j, 'S', Colour::Red),
+                Object::End => maze.set(i, j, 'E', Colour::Red),
+                Object::Empty => {}
+            }
+        }
+    }
+
+    let tiles = get_optimal_tiles(start, opt, &grid);
+    let mut paths = maze.clone();
+    paths.title = format!(
+        "Day 16 - {} tiles on optimal paths with score {}",
+        tiles.len(),
+        opt
+    );
+    for p in tiles {
+        if grid[p.0][p.1] == Object::Empty {
+            paths.set(p.0, p.1, 'O', Colour::Green);
+        }
+    }
+
+    Box::new([maze, paths].into_iter())
+}
+
+/// Validate
+///
+/// Moves are not bounds checked, so the maze has to be walled in, and `Point` packs columns into
+/// 8 bits.
+pub fn validate(input: &str, checks: &mut Checks) {
+    match grid_dim(input) {
+        Some((_, cols)) => checks.packed_hash(cols.saturating_sub(1)),
+        None => checks.check("maze is rectangular", false, "rows of different lengths"),
+    }
+    let gaps = border_gaps(input);
+    checks.check(
+        "maze is surrounded by walls",
+        gaps == 0,
+        format!("{} gaps", gaps),
+    );
+    for c in ['S', 'E'] {
+        let count = input.matches(c).count();
+        checks.check(
+            format!("exactly one '{}'", c),
+            count == 1,
+            format!("{} tiles", count),
+        );
+    }
 }
 
 fn parse_input() -> (Vec<Vec<Object>>, Point) {
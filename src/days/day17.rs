@@ -1,3 +1,8 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use crate::etc::bench::Bench;
+use crate::etc::repl::{arg, Repl};
 use crate::{read_input, Solution, SolutionPair};
 
 pub fn solve() -> SolutionPair {
@@ -9,6 +14,17 @@ pub fn solve() -> SolutionPair {
     (Solution::from(sol1), Solution::from(sol2))
 }
 
+pub fn bench(bench: &mut Bench) {
+    bench.run("parse", parse_input);
+    let (registers, program) = parse_input();
+    bench.run_with(
+        "part1",
+        || registers,
+        |mut registers| part1(&mut registers, &program),
+    );
+    bench.run("part2", || part2(&program));
+}
+
 fn part1(registers: &mut [u64], program: &[u64]) -> String {
     let mut outputs = Vec::new();
     let mut i = 0;
@@ -137,6 +153,138 @@ fn get_combo_operand(operand: u64, registers: &[u64]) -> u64 {
     }
 }
 
+struct Machine {
+    registers: [u64; 3],
+    initial: [u64; 3],
+    program: Vec<u64>,
+    i: usize,
+    outputs: Vec<u64>,
+}
+
+impl Machine {
+    fn step(&mut self) -> Option<String> {
+        if self.i + 1 >= self.program.len() {
+            return None;
+        }
+
+        let (opcode, operand) = (self.program[self.i], self.program[self.i + 1]);
+        let mut line = format!("{:>3}: {} {}", self.i, opcode, operand);
+        let (i_next, output) = run_opcode(self.i, opcode, operand, &mut self.registers);
+        if let Some(output) = output {
+            self.outputs.push(output);
+            line.push_str(&format!(" -> out {}", output));
+        }
+        self.i = i_next;
+
+        Some(line)
+    }
+
+    fn state(&self) -> String {
+        format!(
+            "A={} B={} C={} ip={}",
+            self.registers[0], self.registers[1], self.registers[2], self.i
+        )
+    }
+}
+
+pub fn repl(repl: &mut Repl) {
+    let (registers, program) = parse_input();
+    let machine = Rc::new(RefCell::new(Machine {
+        registers,
+        initial: registers,
+        program,
+        i: 0,
+        outputs: Vec::new(),
+    }));
+
+    let m = Rc::clone(&machine);
+    repl.command(
+        "regs",
+        "regs\n    show the registers and the instruction pointer",
+        move |_| Ok(m.borrow().state()),
+    );
+
+    let m = Rc::clone(&machine);
+    repl.command(
+        "step",
+        "step [n]\n    run the next n instructions (default 1)",
+        move |args| {
+            let n: usize = if args.is_empty() {
+                1
+            } else {
+                arg(args, 0, "n")?
+            };
+            let mut machine = m.borrow_mut();
+            let mut lines = Vec::with_capacity(n + 1);
+            for _ in 0..n {
+                match machine.step() {
+                    Some(line) => lines.push(line),
+                    None => {
+                        lines.push(String::from("halted"));
+                        break;
+                    }
+                }
+            }
+            lines.push(machine.state());
+
+            Ok(lines.join("\n"))
+        },
+    );
+
+    let m = Rc::clone(&machine);
+    repl.command("run", "run\n    run until the program halts", move |_| {
+        let mut machine = m.borrow_mut();
+        while machine.step().is_some() {}
+        let outputs: Vec<String> = machine.outputs.iter().map(|o| o.to_string()).collect();
+
+        Ok(format!("{}\n{}", outputs.join(","), machine.state()))
+    });
+
+    let m = Rc::clone(&machine);
+    repl.command(
+        "set",
+        "set <a|b|c> <value>\n    set a register",
+        move |args| {
+            let register: String = arg(args, 0, "a|b|c")?;
+            let i = match register.as_str() {
+                "a" | "A" => 0,
+                "b" | "B" => 1,
+                "c" | "C" => 2,
+                _ => return Err(format!("Unknown register: {}", register)),
+            };
+            let mut machine = m.borrow_mut();
+            machine.registers[i] = arg(args, 1, "value")?;
+
+            Ok(machine.state())
+        },
+    );
+
+    let m = Rc::clone(&machine);
+    repl.command(
+        "reset",
+        "reset\n    restore the initial registers and clear the output",
+        move |_| {
+            let mut machine = m.borrow_mut();
+            machine.registers = machine.initial;
+            machine.i = 0;
+            machine.outputs.clear();
+
+            Ok(machine.state())
+        },
+    );
+
+    repl.command("program", "program\n    list the instructions", move |_| {
+        let machine = machine.borrow();
+        Ok(machine
+            .program
+            .chunks(2)
+            .enumerate()
+            .map(|(i, op)| format!("{:>3}: {} {}", 2 * i, op[0], op[1]))
+            .collect::<Vec<_>>()
+            .join("\n"))
+    });
+}
+
 fn parse_input() -> ([u64; 3], Vec<u64>) {
     let mut lines = read_input!(17)
         .trim()
@@ -1,63 +1,97 @@
 use std::collections::VecDeque;
 use std::hash::{Hash, Hasher};
 
-use hashbrown::{HashMap, HashSet};
-
+use crate::etc::bench::Bench;
+use crate::etc::explain::Explanation;
+use crate::etc::params;
+use crate::etc::validate::Checks;
+use crate::etc::visualize::{Colour, Frame, Frames};
+use crate::utils::hash::{FastMap, FastSet};
 use crate::{read_input, Solution, SolutionPair};
 
-const DIM: Point = Point(71, 71);
 const DIRS: [Direction; 4] = [
     Direction::Up,
     Direction::Right,
     Direction::Down,
     Direction::Left,
 ];
-const TIME: usize = 1024;
 
 pub fn solve() -> SolutionPair {
+    let params = Params::load();
     let grid = parse_input();
 
-    let sol1 = part1(&grid);
-    let sol2 = part2(&grid);
+    let sol1 = part1(&grid, &params);
+    let sol2 = part2(&grid, &params);
 
     (Solution::from(sol1), Solution::from(sol2))
 }
 
-fn part1(grid: &HashMap<Point, usize>) -> usize {
-    bfs(TIME, grid).unwrap()
+pub fn bench(bench: &mut Bench) {
+    let params = Params::load();
+    bench.run("parse", parse_input);
+    let grid = parse_input();
+    bench.run("part1", || part1(&grid, &params));
+    bench.run("part2", || part2(&grid, &params));
+}
+
+/// Params
+///
+/// Size of the memory space and the number of bytes fallen in part 1.
+struct Params {
+    dim: Point,
+    time: usize,
+}
+
+impl Params {
+    fn load() -> Self {
+        Self {
+            dim: Point(params::get(18, "width", 71), params::get(18, "height", 71)),
+            time: params::get(18, "time", 1024),
+        }
+    }
+}
+
+fn part1(grid: &FastMap<Point, usize>, params: &Params) -> usize {
+    bfs(params.time, params.dim, grid).unwrap()
 }
 
-fn part2(grid: &HashMap<Point, usize>) -> String {
-    let mut left = TIME;
+fn part2(grid: &FastMap<Point, usize>, params: &Params) -> String {
+    let (p, _) = blocking_byte(grid, params);
+    format!("{},{}", p.0 - 1, p.1 - 1)
+}
+
+/// The first byte that cuts the exit off and its index.
+fn blocking_byte(grid: &FastMap<Point, usize>, params: &Params) -> (Point, usize) {
+    let mut left = params.time;
     let mut right = grid.len() + 1;
     while right - left > 1 {
         let mid = (left + right) / 2;
-        if bfs(mid, grid).is_none() {
+        if bfs(mid, params.dim, grid).is_none() {
             right = mid;
         } else {
             left = mid;
         }
     }
 
-    let (p, _) = grid.iter().find(|(_, t)| right - 1 == **t).unwrap();
-    format!("{},{}", p.0 - 1, p.1 - 1)
+    let (p, t) = grid.iter().find(|(_, t)| right - 1 == **t).unwrap();
+    (*p, *t)
 }
 
-fn bfs(time: usize, grid: &HashMap<Point, usize>) -> Option<usize> {
-    let mut seen = HashSet::new();
+fn bfs(time: usize, dim: Point, grid: &FastMap<Point, usize>) -> Option<usize> {
+    let mut seen = FastSet::default();
     let mut queue = VecDeque::new();
     queue.push_back((0, Point(1, 1)));
     seen.insert(Point(1, 1));
     while !queue.is_empty() {
         let (d, p) = queue.pop_front().unwrap();
 
-        if p == DIM {
+        if p == dim {
             return Some(d);
         }
 
         for dir in DIRS {
             let pc = p.move_dir(dir);
-            if !pc.inside_dim() || seen.contains(&pc) {
+            if !pc.inside_dim(dim) || seen.contains(&pc) {
                 continue;
             }
 
@@ -75,7 +109,148 @@ fn bfs(time: usize, grid: &HashMap<Point, usize>) -> Option<usize> {
     None
 }
 
-fn parse_input() -> HashMap<Point, usize> {
+/// The byte blocking the exit and the last shortest path before it falls.
+pub fn explain() -> Explanation {
+    let params = Params::load();
+    let grid = parse_input();
+    let (p, t) = blocking_byte(&grid, &params);
+    let path = shortest_path(t, params.dim, &grid).unwrap();
+    let mut explanation = Explanation::default();
+
+    explanation
+        .section("Blocking byte")
+        .entry("position", format!("{},{}", p.0 - 1, p.1 - 1))
+        .entry("index", t);
+
+    let steps: Vec<String> = path
+        .iter()
+        .map(|p| format!("{},{}", p.0 - 1, p.1 - 1))
+        .collect();
+    explanation
+        .section(format!("Last path, with {} bytes fallen", t))
+        .entry("steps", steps.len() - 1)
+        .entry("path", steps.join(" "));
+
+    explanation
+}
+
+/// Shortest path from the start to the exit, both included, with the first `time` bytes fallen.
+fn shortest_path(time: usize, dim: Point, grid: &FastMap<Point, usize>) -> Option<Vec<Point>> {
+    let mut previous = FastMap::default();
+    let mut queue = VecDeque::new();
+    queue.push_back(Point(1, 1));
+    previous.insert(Point(1, 1), Point(1, 1));
+    while let Some(p) = queue.pop_front() {
+        if p == dim {
+            let mut path = vec![p];
+            while path[path.len() - 1] != Point(1, 1) {
+                path.push(previous[&path[path.len() - 1]]);
+            }
+            path.reverse();
+            return Some(path);
+        }
+
+        for dir in DIRS {
+            let pc = p.move_dir(dir);
+            if !pc.inside_dim(dim) || previous.contains_key(&pc) {
+                continue;
+            }
+
+            if grid.get(&pc).is_some_and(|ns| *ns < time) {
+                continue;
+            }
+
+            queue.push_back(pc);
+            previous.insert(pc, p);
+        }
+    }
+
+    None
+}
+
+/// Falling bytes, one frame per byte until the exit is no longer reachable.
+pub fn visualize() -> Frames {
+    let dim = Params::load().dim;
+    let grid = parse_input();
+    let mut bytes: Vec<(Point, usize)> = grid.iter().map(|(p, t)| (*p, *t)).collect();
+    bytes.sort_by_key(|(_, t)| *t);
+
+    let mut frame = Frame::new(String::new(), dim.1, dim.0);
+    let mut time = 0..=bytes.len();
+    let mut blocked = false;
+    Box::new(std::iter::from_fn(move || {
+        let t = time.next().filter(|_| !blocked)?;
+        if t > 0 {
+            let (p, _) = bytes[t - 1];
+            frame.set(p.1 - 1, p.0 - 1, '#', Colour::White);
+        }
+
+        let mut out = frame.clone();
+        match bfs(t, dim, &grid) {
+            Some(d) => out.title = format!("Day 18 - {} bytes, exit in {} steps", t, d),
+            None => {
+                let (p, _) = bytes[t - 1];
+                out.title = format!("Day 18 - {} bytes, blocked by {},{}", t, p.0 - 1, p.1 - 1);
+                out.set(p.1 - 1, p.0 - 1, '#', Colour::Red);
+                blocked = true;
+            }
+        }
+
+        Some(out)
+    }))
+}
+
+/// Validate
+///
+/// Part 1 drops the first `time` bytes onto the `dim` sized memory space, and `Point` packs the
+/// shifted `y` coordinate into 8 bits.
+pub fn validate(input: &str, checks: &mut Checks) {
+    let Params { dim, time } = Params::load();
+    let lines: Vec<&str> = input
+        .trim()
+        .lines()
+        .map(|line| line.trim())
+        .filter(|line| !line.is_empty())
+        .collect();
+    let bytes: Vec<(usize, usize)> = lines
+        .iter()
+        .filter_map(|line| {
+            let (x, y) = line.split_once(',')?;
+            Some((x.parse().ok()?, y.parse().ok()?))
+        })
+        .collect();
+
+    checks.check(
+        "every line is an `x,y` byte",
+        bytes.len() == lines.len(),
+        format!("{} of {} lines", bytes.len(), lines.len()),
+    );
+    checks.check(
+        format!("at least time = {} bytes", time),
+        bytes.len() >= time,
+        format!("{} bytes", bytes.len()),
+    );
+
+    let outside = bytes
+        .iter()
+        .filter(|&&(x, y)| x >= dim.0 || y >= dim.1)
+        .count();
+    checks.check(
+        format!("bytes fall inside the {}x{} memory space", dim.0, dim.1),
+        outside == 0,
+        format!("{} bytes outside", outside),
+    );
+
+    let unique: FastSet<_> = bytes.iter().collect();
+    checks.check(
+        "no byte falls twice",
+        unique.len() == bytes.len(),
+        format!("{} duplicates", bytes.len() - unique.len()),
+    );
+    checks.packed_hash(bytes.iter().map(|&(_, y)| y + 1).max().unwrap_or(0));
+}
+
+fn parse_input() -> FastMap<Point, usize> {
     read_input!(18)
         .trim()
         .split("\n")
@@ -112,8 +287,8 @@ impl Point {
         }
     }
 
-    pub fn inside_dim(&self) -> bool {
-        self.0 > 0 && self.0 <= DIM.0 && self.1 > 0 && self.1 <= DIM.1 // added 1 to x and y coords
+    pub fn inside_dim(&self, dim: Point) -> bool {
+        self.0 > 0 && self.0 <= dim.0 && self.1 > 0 && self.1 <= dim.1 // added 1 to x and y coords
     }
 }
 
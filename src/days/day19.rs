@@ -1,5 +1,6 @@
-use hashbrown::HashMap;
-
+use crate::etc::bench::Bench;
+use crate::utils::hash::FastMap;
+use crate::utils::random::Rng;
 use crate::{read_input, Solution, SolutionPair};
 
 pub fn solve() -> SolutionPair {
@@ -7,7 +8,7 @@ pub fn solve() -> SolutionPair {
 
     let scores: Vec<usize> = designs
         .iter()
-        .map(|design| count_combinations(&design, &towels, &mut HashMap::new()))
+        .map(|design| count_combinations(&design, &towels, &mut FastMap::default()))
         .collect();
 
     let sol1 = part1(&scores);
@@ -16,6 +17,19 @@ pub fn solve() -> SolutionPair {
     (Solution::from(sol1), Solution::from(sol2))
 }
 
+/// Both parts share the counted combinations of every design.
+pub fn bench(bench: &mut Bench) {
+    bench.run("parse", parse_input);
+    let (towels, designs) = parse_input();
+    bench.run("parts", || {
+        let scores: Vec<usize> = designs
+            .iter()
+            .map(|design| count_combinations(design, &towels, &mut FastMap::default()))
+            .collect();
+        (part1(&scores), part2(&scores))
+    });
+}
+
 fn part1(scores: &[usize]) -> usize {
     scores.iter().filter(|score| **score > 0).count()
 }
@@ -27,7 +41,7 @@ fn part2(scores: &[usize]) -> usize {
 fn count_combinations<'a>(
     design: &'a str,
     towels: &[&str],
-    seen: &mut HashMap<&'a str, usize>,
+    seen: &mut FastMap<&'a str, usize>,
 ) -> usize {
     if design.is_empty() {
         return 1;
@@ -60,3 +74,38 @@ fn parse_input<'a>() -> (Vec<&'a str>, Vec<&'a str>) {
 
     (towels, designs)
 }
+
+/// Swaps the stripe colours with a random permutation of them and shuffles the towels, which
+/// keeps the number of arrangements of every design.
+pub fn anonymize(input: &str, rng: &mut Rng) -> String {
+    let mut lines = input
+        .lines()
+        .map(|line| line.trim())
+        .filter(|line| !line.is_empty());
+    let mut towels: Vec<&str> = lines.next().unwrap_or("").split(", ").collect();
+    let designs: Vec<&str> = lines.collect();
+    rng.shuffle(&mut towels);
+
+    let mut colours: Vec<char> = input.chars().filter(char::is_ascii_lowercase).collect();
+    colours.sort();
+    colours.dedup();
+    let mut permuted = colours.clone();
+    rng.shuffle(&mut permuted);
+    let recolour = |pattern: &str| -> String {
+        pattern
+            .chars()
+            .map(|c| match colours.binary_search(&c) {
+                Ok(i) => permuted[i],
+                Err(_) => c,
+            })
+            .collect()
+    };
+
+    let towels: Vec<String> = towels.iter().map(|towel| recolour(towel)).collect();
+    let designs: Vec<String> = designs
+        .iter()
+        .map(|design| recolour(design) + "\n")
+        .collect();
+
+    format!("{}\n\n{}", towels.join(", "), designs.concat())
+}
@@ -1,8 +1,10 @@
 use std::collections::VecDeque;
 use std::hash::{Hash, Hasher};
 
-use hashbrown::HashMap;
-
+use crate::etc::bench::Bench;
+use crate::etc::params;
+use crate::etc::validate::{border_gaps, grid_dim, Checks};
+use crate::utils::hash::FastMap;
 use crate::{read_input, Solution, SolutionPair};
 
 const DIRS: [Direction; 4] = [
@@ -13,6 +15,7 @@ const DIRS: [Direction; 4] = [
 ];
 
 pub fn solve() -> SolutionPair {
+    let params = Params::load();
     let grid = parse_input();
     let start = get_object(Object::Start, &grid);
     let end = get_object(Object::End, &grid);
@@ -20,38 +23,82 @@ pub fn solve() -> SolutionPair {
     let dist_end = get_distances(end, &grid);
     let dim = Point(grid.len(), grid[0].len());
 
-    let sol1 = part1_new(start, dim, &dist_start, &dist_end);
-    let sol2 = part2(start, dim, &dist_start, &dist_end);
+    let sol1 = part1_new(start, dim, &dist_start, &dist_end, &params);
+    let sol2 = part2(start, dim, &dist_start, &dist_end, &params);
 
     (Solution::from(sol1), Solution::from(sol2))
 }
 
+/// The distances from the start and the end are shared by both parts.
+pub fn bench(bench: &mut Bench) {
+    let params = Params::load();
+    bench.run("parse", parse_input);
+    let grid = parse_input();
+    let (start, end) = (
+        get_object(Object::Start, &grid),
+        get_object(Object::End, &grid),
+    );
+    bench.run("distances", || {
+        (get_distances(start, &grid), get_distances(end, &grid))
+    });
+    let dist_start = get_distances(start, &grid);
+    let dist_end = get_distances(end, &grid);
+    let dim = Point(grid.len(), grid[0].len());
+    bench.run("part1", || {
+        part1_new(start, dim, &dist_start, &dist_end, &params)
+    });
+    bench.run("part2", || {
+        part2(start, dim, &dist_start, &dist_end, &params)
+    });
+}
+
+/// Params
+///
+/// Picoseconds a cheat has to save and the maximum cheat length in each part.
+struct Params {
+    threshold: usize,
+    cheat1: usize,
+    cheat2: usize,
+}
+
+impl Params {
+    fn load() -> Self {
+        Self {
+            threshold: params::get(20, "threshold", 100),
+            cheat1: params::get(20, "cheat1", 2),
+            cheat2: params::get(20, "cheat2", 20),
+        }
+    }
+}
+
 fn part1_new(
     start: Point,
     dim: Point,
-    dist_start: &HashMap<Point, usize>,
-    dist_end: &HashMap<Point, usize>,
+    dist_start: &FastMap<Point, usize>,
+    dist_end: &FastMap<Point, usize>,
+    params: &Params,
 ) -> usize {
-    let fair_score = *dist_end.get(&start).unwrap() - 100;
-    count_cheats(2, fair_score, dim, dist_start, dist_end)
+    let fair_score = *dist_end.get(&start).unwrap() - params.threshold;
+    count_cheats(params.cheat1, fair_score, dim, dist_start, dist_end)
 }
 
 fn part2(
     start: Point,
     dim: Point,
-    dist_start: &HashMap<Point, usize>,
-    dist_end: &HashMap<Point, usize>,
+    dist_start: &FastMap<Point, usize>,
+    dist_end: &FastMap<Point, usize>,
+    params: &Params,
 ) -> usize {
-    let fair_score = *dist_end.get(&start).unwrap() - 100;
-    count_cheats(20, fair_score, dim, dist_start, dist_end)
+    let fair_score = *dist_end.get(&start).unwrap() - params.threshold;
+    count_cheats(params.cheat2, fair_score, dim, dist_start, dist_end)
 }
 
 fn count_cheats(
     t: usize,
     fair_score: usize,
     dim: Point,
-    dist_start: &HashMap<Point, usize>,
-    dist_end: &HashMap<Point, usize>,
+    dist_start: &FastMap<Point, usize>,
+    dist_end: &FastMap<Point, usize>,
 ) -> usize {
     let mut result = 0;
     for (&p, &ds) in dist_start {
@@ -97,15 +144,15 @@ fn count_cheats(
     result
 }
 
-fn check_cheat(ds: usize, goal: usize, p: Point, dists: &HashMap<Point, usize>) -> bool {
+fn check_cheat(ds: usize, goal: usize, p: Point, dists: &FastMap<Point, usize>) -> bool {
     match dists.get(&p) {
         Some(de) => *de + ds <= goal,
         None => false,
     }
 }
 
-fn get_distances(goal: Point, grid: &Vec<Vec<Object>>) -> HashMap<Point, usize> {
-    let mut seen = HashMap::new();
+fn get_distances(goal: Point, grid: &Vec<Vec<Object>>) -> FastMap<Point, usize> {
+    let mut seen = FastMap::default();
     let mut queue = VecDeque::new();
     queue.push_back((goal, 0));
     seen.insert(goal, 0);
@@ -135,6 +182,41 @@ fn get_object(object: Object, grid: &Vec<Vec<Object>>) -> Point {
     unreachable!()
 }
 
+/// Validate
+///
+/// Moves are not bounds checked, so the racetrack has to be walled in, and `Point` packs columns
+/// into 8 bits.
+pub fn validate(input: &str, checks: &mut Checks) {
+    match grid_dim(input) {
+        Some((_, cols)) => checks.packed_hash(cols.saturating_sub(1)),
+        None => checks.check(
+            "racetrack is rectangular",
+            false,
+            "rows of different lengths",
+        ),
+    }
+    let gaps = border_gaps(input);
+    checks.check(
+        "racetrack is surrounded by walls",
+        gaps == 0,
+        format!("{} gaps", gaps),
+    );
+    for c in ['S', 'E'] {
+        let count = input.matches(c).count();
+        checks.check(
+            format!("exactly one '{}'", c),
+            count == 1,
+            format!("{} tiles", count),
+        );
+    }
+    let unknown = input.chars().filter(|c| !".#SE\r\n".contains(*c)).count();
+    checks.check(
+        "racetrack only contains '.', '#', 'S' and 'E'",
+        unknown == 0,
+        format!("{} other characters", unknown),
+    );
+}
+
 fn parse_input() -> Vec<Vec<Object>> {
     read_input!(20)
         .trim()
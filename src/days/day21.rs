@@ -1,7 +1,8 @@
 use std::fmt::{Debug, Formatter, Write};
 
-use hashbrown::HashMap;
-
+use crate::etc::bench::Bench;
+use crate::etc::params;
+use crate::utils::hash::FastMap;
 use crate::{read_input, Solution, SolutionPair};
 
 const NUMERIC_KEYPAD: [[NumericKey; 3]; 4] = [
@@ -45,27 +46,53 @@ const KEYS: [DirectionalKey; 5] = [
 ];
 
 pub fn solve() -> SolutionPair {
+    let params = Params::load();
     let codes = parse_input();
 
-    let sol1 = part1(&codes);
-    let sol2 = part2(&codes);
+    let sol1 = part1(&codes, &params);
+    let sol2 = part2(&codes, &params);
 
     (Solution::from(sol1), Solution::from(sol2))
 }
 
-fn part1(codes: &Vec<Vec<NumericKey>>) -> usize {
-    let lengths = get_sequence_lengths(2);
+pub fn bench(bench: &mut Bench) {
+    let params = Params::load();
+    bench.run("parse", parse_input);
+    let codes = parse_input();
+    bench.run("part1", || part1(&codes, &params));
+    bench.run("part2", || part2(&codes, &params));
+}
+
+/// Params
+///
+/// Number of directional keypad robots in each part.
+struct Params {
+    depth1: usize,
+    depth2: usize,
+}
+
+impl Params {
+    fn load() -> Self {
+        Self {
+            depth1: params::get(21, "depth1", 2),
+            depth2: params::get(21, "depth2", 25),
+        }
+    }
+}
+
+fn part1(codes: &Vec<Vec<NumericKey>>, params: &Params) -> usize {
+    let lengths = get_sequence_lengths(params.depth1);
     get_result(codes, &lengths)
 }
 
-fn part2(codes: &Vec<Vec<NumericKey>>) -> usize {
-    let lengths = get_sequence_lengths(25);
+fn part2(codes: &Vec<Vec<NumericKey>>, params: &Params) -> usize {
+    let lengths = get_sequence_lengths(params.depth2);
     get_result(codes, &lengths)
 }
 
 fn get_result(
     codes: &Vec<Vec<NumericKey>>,
-    lengths: &HashMap<DirectionalKey, HashMap<DirectionalKey, usize>>,
+    lengths: &FastMap<DirectionalKey, FastMap<DirectionalKey, usize>>,
 ) -> usize {
     codes
         .iter()
@@ -75,7 +102,7 @@ fn get_result(
 
 fn get_shortest_path_len(
     code: &[NumericKey],
-    lengths: &HashMap<DirectionalKey, HashMap<DirectionalKey, usize>>,
+    lengths: &FastMap<DirectionalKey, FastMap<DirectionalKey, usize>>,
 ) -> usize {
     let mut result = 0;
     let mut cur = DirectionalKey::Activate;
@@ -87,22 +114,22 @@ fn get_shortest_path_len(
     result
 }
 
-fn get_sequence_lengths(n: usize) -> HashMap<DirectionalKey, HashMap<DirectionalKey, usize>> {
-    let mut lengths = HashMap::new();
+fn get_sequence_lengths(n: usize) -> FastMap<DirectionalKey, FastMap<DirectionalKey, usize>> {
+    let mut lengths = FastMap::default();
     for from_key in KEYS {
-        let mut key_lengths = HashMap::new();
+        let mut key_lengths = FastMap::default();
         for to_key in KEYS {
             key_lengths.insert(to_key, 1);
         }
         lengths.insert(from_key, key_lengths);
     }
 
-    let mut shortest_paths = HashMap::new();
+    let mut shortest_paths = FastMap::default();
 
     for _ in 0..n {
-        let mut next = HashMap::new();
+        let mut next = FastMap::default();
         for from_key in KEYS {
-            let mut key_lengths = HashMap::new();
+            let mut key_lengths = FastMap::default();
             for to_key in KEYS {
                 let path = shortest_paths
                     .entry((from_key, to_key))
@@ -1,49 +1,97 @@
-use hashbrown::{HashMap, HashSet};
+#[cfg(feature = "parallel")]
 use rayon::iter::{IntoParallelRefIterator, ParallelIterator};
 
-use crate::{read_input, Solution, SolutionPair};
+use std::io;
 
-const S_MAX: usize = 2000;
+use crate::etc::bench::Bench;
+use crate::etc::params;
+use crate::utils::file::LineReader;
+use crate::utils::hash::{FastMap, FastSet};
+use crate::utils::random::Rng;
+use crate::{read_input, Solution, SolutionPair};
 
 pub fn solve() -> SolutionPair {
+    let params = Params::load();
     let secrets = parse_input();
 
-    let sol1 = part1(&secrets);
-    let sol2 = part2(&secrets);
+    let sol1 = part1(&secrets, &params);
+    let sol2 = part2(&secrets, &params);
 
     (Solution::from(sol1), Solution::from(sol2))
 }
 
-fn part1(secrets: &[i64]) -> i64 {
+/// Runs every buyer as their secret is read, only the sequence totals are kept.
+pub fn solve_stream(lines: &mut LineReader) -> io::Result<SolutionPair> {
+    let params = Params::load();
+    let mut sol1 = 0;
+    let mut sequences = FastMap::default();
+    lines.for_each(|line| {
+        let secret = line.parse::<i64>().unwrap();
+        sol1 += iterate_secret(secret, params.s_max);
+        update_sequences(secret, params.s_max, &mut sequences);
+    })?;
+    let sol2 = *sequences.values().max().unwrap();
+
+    Ok((Solution::from(sol1), Solution::from(sol2)))
+}
+
+pub fn bench(bench: &mut Bench) {
+    let params = Params::load();
+    bench.run("parse", parse_input);
+    let secrets = parse_input();
+    bench.run("part1", || part1(&secrets, &params));
+    bench.run("part2", || part2(&secrets, &params));
+}
+
+/// Params
+///
+/// Number of secrets each buyer generates.
+struct Params {
+    s_max: usize,
+}
+
+impl Params {
+    fn load() -> Self {
+        Self {
+            s_max: params::get(22, "s_max", 2000),
+        }
+    }
+}
+
+fn part1(secrets: &[i64], params: &Params) -> i64 {
+    #[cfg(feature = "parallel")]
+    let secrets = secrets.par_iter();
+    #[cfg(not(feature = "parallel"))]
+    let secrets = secrets.iter();
+
     secrets
-        .par_iter()
-        .map(|secret| iterate_secret(*secret))
+        .map(|secret| iterate_secret(*secret, params.s_max))
         .sum()
 }
 
-fn iterate_secret(mut secret: i64) -> i64 {
-    for _ in 0..S_MAX {
+fn iterate_secret(mut secret: i64, s_max: usize) -> i64 {
+    for _ in 0..s_max {
         secret = next_secret(secret);
     }
 
     secret
 }
 
-fn part2(secrets: &[i64]) -> i64 {
-    let mut sequences = HashMap::new();
+fn part2(secrets: &[i64], params: &Params) -> i64 {
+    let mut sequences = FastMap::default();
     secrets
         .iter()
-        .for_each(|&secret| update_sequences(secret, &mut sequences));
+        .for_each(|&secret| update_sequences(secret, params.s_max, &mut sequences));
 
     *sequences.values().max().unwrap()
 }
 
-fn update_sequences(secret: i64, sequences: &mut HashMap<u32, i64>) {
-    let mut seen = HashSet::new();
+fn update_sequences(secret: i64, s_max: usize, sequences: &mut FastMap<u32, i64>) {
+    let mut seen = FastSet::default();
     let mut sequence = 0;
 
     let mut prev_secret = secret;
-    for i in 0..S_MAX {
+    for i in 0..s_max {
         let secret = next_secret(prev_secret);
         let delta = (secret % 10) - (prev_secret % 10);
         let delta = (delta + 9) as u32; // Delta between 0 & 18 inclusive (5 bits)
@@ -74,9 +122,26 @@ fn next_secret(mut secret: i64) -> i64 {
 }
 
 fn parse_input() -> Vec<i64> {
-    read_input!(22)
-        .trim()
-        .split("\n")
-        .map(|line| line.trim().parse::<i64>().unwrap())
-        .collect()
+    let mut secrets = Vec::new();
+    LineReader::new(read_input!(22).as_bytes())
+        .for_each(|line| secrets.push(line.parse::<i64>().unwrap()))
+        .unwrap();
+
+    secrets
+}
+
+/// One initial secret per buyer, using the full 24-bit secret range.
+pub fn generate(size: usize, rng: &mut Rng) -> String {
+    let mut input = String::new();
+    for _ in 0..size {
+        input.push_str(&format!("{}\n", rng.range(1, 16777215)));
+    }
+
+    input
+}
+
+/// Re-seeds every buyer with a random initial secret.
+pub fn anonymize(input: &str, rng: &mut Rng) -> String {
+    let buyers = input.lines().filter(|line| !line.trim().is_empty()).count();
+    generate(buyers, rng)
 }
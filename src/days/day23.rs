@@ -1,5 +1,11 @@
-use hashbrown::{HashMap, HashSet};
+use std::collections::BTreeSet;
+use std::rc::Rc;
 
+use crate::etc::bench::Bench;
+use crate::etc::explain::Explanation;
+use crate::etc::repl::{arg, Repl};
+use crate::utils::hash::{FastMap, FastSet};
+use crate::utils::random::Rng;
 use crate::{read_input, Solution, SolutionPair};
 
 pub fn solve() -> SolutionPair {
@@ -11,8 +17,15 @@ pub fn solve() -> SolutionPair {
     (Solution::from(sol1), Solution::from(sol2))
 }
 
-fn part1(graph: &HashMap<&'static str, HashSet<&'static str>>) -> usize {
-    let mut clique3 = HashSet::new();
+pub fn bench(bench: &mut Bench) {
+    bench.run("parse", parse_input);
+    let graph = parse_input();
+    bench.run("part1", || part1(&graph));
+    bench.run("part2", || part2c(&graph));
+}
+
+fn part1(graph: &FastMap<&'static str, FastSet<&'static str>>) -> usize {
+    let mut clique3 = FastSet::default();
     for (&v0, ns) in graph {
         for &v1 in ns {
             for &v2 in ns.intersection(graph.get(v1).unwrap()) {
@@ -28,25 +41,53 @@ fn part1(graph: &HashMap<&'static str, HashSet<&'static str>>) -> usize {
     clique3.len()
 }
 
-fn part2c(graph: &HashMap<&'static str, HashSet<&'static str>>) -> String {
+fn part2c(graph: &FastMap<&'static str, FastSet<&'static str>>) -> String {
+    maximum_clique(graph).join(",")
+}
+
+/// Members of the maximum clique, sorted.
+fn maximum_clique(graph: &FastMap<&'static str, FastSet<&'static str>>) -> Vec<&'static str> {
     let p = graph.keys().map(|v| *v).collect();
-    let mut r = HashSet::new();
-    let x = HashSet::new();
+    let mut r = FastSet::default();
+    let x = FastSet::default();
 
     let best = get_maximum_clique(&mut r, p, x, graph);
-    let mut best = Vec::from_iter(best.iter().map(|v| *v));
-    best.sort();
+    sorted(best.iter())
+}
+
+/// Members of the maximum clique with their connections outside of it.
+pub fn explain() -> Explanation {
+    let graph = parse_input();
+    let clique = maximum_clique(&graph);
+    let mut explanation = Explanation::default();
+
+    explanation.section(format!("Maximum clique of {} computers", clique.len()));
+    for v in &clique {
+        let outside = sorted(graph[v].iter().filter(|u| !clique.contains(u)));
+        explanation.entry(
+            v,
+            format!(
+                "connected to the other {}, outside: {}",
+                clique.len() - 1,
+                if outside.is_empty() {
+                    String::from("-")
+                } else {
+                    outside.join(",")
+                }
+            ),
+        );
+    }
 
-    best.join(",")
+    explanation
 }
 
 // Bron Kerbosch Algorithm
 fn get_maximum_clique(
-    r: &mut HashSet<&'static str>,
-    mut p: HashSet<&'static str>,
-    mut x: HashSet<&'static str>,
-    graph: &HashMap<&'static str, HashSet<&'static str>>,
-) -> HashSet<&'static str> {
+    r: &mut FastSet<&'static str>,
+    mut p: FastSet<&'static str>,
+    mut x: FastSet<&'static str>,
+    graph: &FastMap<&'static str, FastSet<&'static str>>,
+) -> FastSet<&'static str> {
     if p.is_empty() && x.is_empty() {
         return r.clone();
     }
@@ -54,7 +95,7 @@ fn get_maximum_clique(
     let u = p.union(&x).map(|v| *v).next().unwrap(); // pivot
     let p1 = &p - graph.get(u).unwrap();
 
-    let mut best: Option<HashSet<&'static str>> = None;
+    let mut best: Option<FastSet<&'static str>> = None;
     for v in p1 {
         r.insert(v);
         let nv = graph.get(v).unwrap();
@@ -76,8 +117,59 @@ fn get_maximum_clique(
     best.unwrap_or_default()
 }
 
-fn parse_input() -> HashMap<&'static str, HashSet<&'static str>> {
-    let mut graph = HashMap::new();
+pub fn repl(repl: &mut Repl) {
+    let graph = Rc::new(parse_input());
+
+    let g = Rc::clone(&graph);
+    repl.command(
+        "neighbours",
+        "neighbours <node>\n    list the computers connected to the node",
+        move |args| {
+            let node: String = arg(args, 0, "node")?;
+            let ns = get_neighbours(&node, &g)?;
+            Ok(format!("{} ({})", sorted(ns.iter()).join(","), ns.len()))
+        },
+    );
+
+    let g = Rc::clone(&graph);
+    repl.command(
+        "connected",
+        "connected <node> <node>\n    check whether two computers are connected",
+        move |args| {
+            let u: String = arg(args, 0, "node")?;
+            let v: String = arg(args, 1, "node")?;
+            let connected = get_neighbours(&u, &g)?.contains(v.as_str());
+            Ok(String::from(if connected { "yes" } else { "no" }))
+        },
+    );
+
+    repl.command(
+        "common",
+        "common <node> <node>\n    list the computers connected to both nodes",
+        move |args| {
+            let u: String = arg(args, 0, "node")?;
+            let v: String = arg(args, 1, "node")?;
+            let common = get_neighbours(&u, &graph)?.intersection(get_neighbours(&v, &graph)?);
+            Ok(sorted(common).join(","))
+        },
+    );
+}
+
+fn get_neighbours<'a>(
+    node: &str,
+    graph: &'a FastMap<&'static str, FastSet<&'static str>>,
+) -> Result<&'a FastSet<&'static str>, String> {
+    graph.get(node).ok_or(format!("Unknown computer: {}", node))
+}
+
+fn sorted<'a>(nodes: impl Iterator<Item = &'a &'static str>) -> Vec<&'static str> {
+    let mut nodes: Vec<&'static str> = nodes.cloned().collect();
+    nodes.sort();
+    nodes
+}
+
+fn parse_input() -> FastMap<&'static str, FastSet<&'static str>> {
+    let mut graph = FastMap::default();
 
     read_input!(23)
         .trim()
@@ -88,9 +180,108 @@ fn parse_input() -> HashMap<&'static str, HashSet<&'static str>> {
             let u = edge.next().unwrap();
             let v = edge.next().unwrap();
 
-            graph.entry(u).or_insert(HashSet::new()).insert(v);
-            graph.entry(v).or_insert(HashSet::new()).insert(u);
+            graph.entry(u).or_insert(FastSet::default()).insert(v);
+            graph.entry(v).or_insert(FastSet::default()).insert(u);
         });
 
     graph
 }
+
+/// Random network of `size` computers, each linked to about 13 others, with a planted
+/// clique of 13. Names are two letters long like the original input and get longer once
+/// two letters are no longer enough.
+pub fn generate(size: usize, rng: &mut Rng) -> String {
+    let mut name_len = 1;
+    while 26usize.pow(name_len) < size {
+        name_len += 1;
+    }
+    let name_len = name_len.max(2);
+
+    let mut names: Vec<String> = Vec::with_capacity(size);
+    let mut taken = FastSet::default();
+    while names.len() < size {
+        let name: String = (0..name_len)
+            .map(|_| (b'a' + rng.range(0, 25) as u8) as char)
+            .collect();
+        if taken.insert(name.clone()) {
+            names.push(name);
+        }
+    }
+
+    // ordered, so that the same seed always gives the same file
+    let mut edges = BTreeSet::new();
+    for u in 0..size {
+        for _ in 0..6 {
+            let v = rng.index(size);
+            if u != v {
+                edges.insert((u.min(v), u.max(v)));
+            }
+        }
+    }
+
+    let mut clique: Vec<usize> = (0..size).collect();
+    rng.shuffle(&mut clique);
+    clique.truncate(13);
+    for (i, &u) in clique.iter().enumerate() {
+        for &v in &clique[i + 1..] {
+            edges.insert((u.min(v), u.max(v)));
+        }
+    }
+
+    let mut edges: Vec<_> = edges.into_iter().collect();
+    rng.shuffle(&mut edges);
+
+    let mut input = String::new();
+    for (u, v) in edges {
+        input.push_str(&format!("{}-{}\n", names[u], names[v]));
+    }
+
+    input
+}
+
+/// Renames the computers with random two letter names and shuffles the connections. Names
+/// starting with `t` keep starting with `t`, as part 1 depends on it.
+pub fn anonymize(input: &str, rng: &mut Rng) -> String {
+    let edges: Vec<(&str, &str)> = input
+        .lines()
+        .filter_map(|line| line.trim().split_once('-'))
+        .collect();
+    let mut computers: Vec<&str> = edges.iter().flat_map(|&(u, v)| [u, v]).collect();
+    computers.sort();
+    computers.dedup();
+
+    let letters: Vec<char> = ('a'..='z').collect();
+    let mut t_names: Vec<String> = letters.iter().map(|b| format!("t{}", b)).collect();
+    let mut other_names: Vec<String> = letters
+        .iter()
+        .filter(|&&a| a != 't')
+        .flat_map(|&a| letters.iter().map(move |&b| format!("{}{}", a, b)))
+        .collect();
+    rng.shuffle(&mut t_names);
+    rng.shuffle(&mut other_names);
+
+    let names: FastMap<&str, String> = computers
+        .iter()
+        .map(|&computer| {
+            let name = match computer.starts_with('t') {
+                true => t_names.pop(),
+                false => other_names.pop(),
+            };
+            (
+                computer,
+                name.expect("Too many computers for two letter names"),
+            )
+        })
+        .collect();
+
+    let mut lines: Vec<String> = edges
+        .iter()
+        .map(|&(u, v)| match rng.chance(1, 2) {
+            true => format!("{}-{}\n", names[u], names[v]),
+            false => format!("{}-{}\n", names[v], names[u]),
+        })
+        .collect();
+    rng.shuffle(&mut lines);
+
+    lines.concat()
+}
@@ -1,11 +1,18 @@
 use std::cell::RefCell;
 use std::collections::VecDeque;
+use std::fmt::{Display, Formatter};
 use std::hash::Hash;
 use std::rc::Rc;
 
-use hashbrown::HashMap;
+use crate::etc::bench::Bench;
+use crate::etc::explain::Explanation;
+use crate::etc::repl::{arg, Repl};
+use crate::etc::validate::Checks;
+use crate::utils::hash::{FastMap, FastSet};
+use crate::utils::random::Rng;
+use crate::{debug, info, read_input, trace, Solution, SolutionPair};
 
-use crate::{read_input, Solution, SolutionPair};
+const BITS: usize = 45; // width of the x and y inputs
 
 type WireName = &'static str;
 
@@ -18,8 +25,15 @@ pub fn solve() -> SolutionPair {
     (Solution::from(sol1), Solution::from(sol2))
 }
 
+pub fn bench(bench: &mut Bench) {
+    bench.run("parse", parse_input);
+    let (init, gates) = parse_input();
+    bench.run("part1", || part1(&init, &gates));
+    bench.run_with("part2", || gates.clone(), |mut gates| part2(&mut gates));
+}
+
 fn part1(init: &[(WireName, u8)], gates: &[Gate]) -> u64 {
-    let mut undetermined = HashMap::new();
+    let mut undetermined = FastMap::default();
     for gate in gates {
         let gate_shared = Rc::new(RefCell::new(gate.clone()));
         for wire in gate.get_undetermined() {
@@ -68,10 +82,20 @@ fn part1(init: &[(WireName, u8)], gates: &[Gate]) -> u64 {
 }
 
 fn part2(gates: &mut [Gate]) -> String {
-    let mut swaps = Vec::with_capacity(8);
-    while let Some((w0, w1)) = get_swap_wires(gates) {
-        swaps.push(w0);
-        swaps.push(w1);
+    let mut swaps: Vec<WireName> = find_swaps(gates)
+        .into_iter()
+        .flat_map(|(w0, w1, _)| [w0, w1])
+        .collect();
+
+    swaps.sort();
+    swaps.join(",")
+}
+
+/// Swaps the wires back one pair at a time until the circuit is an adder.
+fn find_swaps(gates: &mut [Gate]) -> Vec<(WireName, WireName, Role)> {
+    let mut swaps = Vec::with_capacity(4);
+    while let Some((w0, w1, role)) = get_swap_wires(gates) {
+        swaps.push((w0, w1, role));
 
         for gate in &mut *gates {
             if gate.gout == w0 {
@@ -82,8 +106,21 @@ fn part2(gates: &mut [Gate]) -> String {
         }
     }
 
-    swaps.sort();
-    swaps.join(",")
+    info!("found {} swapped pairs", swaps.len());
+    swaps
+}
+
+/// Swapped pairs of gate outputs and the adder wire that was misplaced by each.
+pub fn explain() -> Explanation {
+    let (_, mut gates) = parse_input();
+    let mut explanation = Explanation::default();
+
+    explanation.section("Swapped pairs");
+    for (w0, w1, role) in find_swaps(&mut gates) {
+        explanation.entry(format!("{} <-> {}", w0, w1), role);
+    }
+
+    explanation
 }
 
 /// Get Wires for Swapping
@@ -99,8 +136,8 @@ fn part2(gates: &mut [Gate]) -> String {
 /// Edge cases are `z_0 = x_0 ^ y_0` and `z_45 = c_44` and are not covered by the current
 /// implementation. They should be simple to add though as they are simplified versions
 /// of the inner bits.
-fn get_swap_wires(gates: &[Gate]) -> Option<(WireName, WireName)> {
-    let x_xor_y: HashMap<_, _> = gates
+fn get_swap_wires(gates: &[Gate]) -> Option<(WireName, WireName, Role)> {
+    let x_xor_y: FastMap<_, _> = gates
         .iter()
         .filter(|gate| {
             if gate.op != Operation::Xor {
@@ -141,7 +178,12 @@ fn get_swap_wires(gates: &[Gate]) -> Option<(WireName, WireName)> {
     }
 
     let zi = *to_fix.first().unwrap();
-    let gates1: HashMap<_, _> = gates
+    trace!(
+        "{} z gates do not follow the adder structure, fixing {}",
+        to_fix.len(),
+        zi.gout
+    );
+    let gates1: FastMap<_, _> = gates
         .iter()
         .map(|gate| {
             let mut wires = gate.get_undetermined();
@@ -154,14 +196,14 @@ fn get_swap_wires(gates: &[Gate]) -> Option<(WireName, WireName)> {
         })
         .collect();
 
-    let mut cs = Vec::with_capacity(45); // carry-overs
+    let mut cs = Vec::with_capacity(BITS); // carry-overs
     cs.push(
         *gates1
             .get(&("x00".to_string(), "y00".to_string(), Operation::And))
             .unwrap(),
     );
 
-    for i in 1..45 {
+    for i in 1..BITS {
         let xor = if let Some(wire) =
             gates1.get(&(format!("x{i:02}"), format!("y{i:02}"), Operation::Xor))
         {
@@ -219,30 +261,216 @@ fn get_swap_wires(gates: &[Gate]) -> Option<(WireName, WireName)> {
             }
         } else {
             let w = *z_wires.iter().find(|w| **w != cur).unwrap();
-            return Some((c, w));
+            debug!("{}: carry-over {} swapped with {}", zi.gout, c, w);
+            return Some((c, w, Role::CarryOver(i)));
         }
     } else if z_wires.contains(&c) {
         let w = *z_wires.iter().find(|w| **w != c).unwrap();
-        return Some((cur, w));
+        debug!(
+            "{}: x{:02} ^ y{:02} ({}) swapped with {}",
+            zi.gout, i, i, cur, w
+        );
+        return Some((cur, w, Role::Sum(i)));
     }
 
     let mut wires = vec![cur, c];
     wires.sort();
     if let Some(wire) = gates1.get(&(wires[0].to_string(), wires[1].to_string(), Operation::Xor)) {
         if *wire != zi.gout {
-            return Some((zi.gout, wire));
+            debug!("{}: output swapped with {}", zi.gout, wire);
+            return Some((zi.gout, wire, Role::Output(i)));
         }
     }
 
     None // never happens for my input, but some cases are not covered, e.g. issue with z45
 }
 
+/// Role
+///
+/// The wire of adder bit `i` whose gate output was swapped, all of them feed the `z_i` XOR gate.
+#[derive(Clone, Copy, Debug)]
+enum Role {
+    /// The carry-over `c_{i-1}` from the previous bit.
+    CarryOver(usize),
+    /// The partial sum `x_i ^ y_i`.
+    Sum(usize),
+    /// The output bit `z_i` itself.
+    Output(usize),
+}
+
+impl Display for Role {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Role::CarryOver(i) => write!(f, "carry-over c{:02} into bit {}", i - 1, i),
+            Role::Sum(i) => write!(f, "partial sum x{:02} ^ y{:02} of bit {}", i, i, i),
+            Role::Output(i) => write!(f, "output z{:02} of bit {}", i, i),
+        }
+    }
+}
+
 fn as_number(bits: &[(WireName, u8)]) -> u64 {
     bits.iter()
         .rev()
         .fold(0, |acc, (_, v)| acc * 2 + (*v as u64))
 }
 
+pub fn repl(repl: &mut Repl) {
+    let (init, gates) = parse_input();
+    let gates: Rc<FastMap<WireName, Gate>> =
+        Rc::new(gates.into_iter().map(|gate| (gate.gout, gate)).collect());
+    let values: Rc<RefCell<FastMap<WireName, u8>>> =
+        Rc::new(RefCell::new(init.into_iter().collect()));
+
+    let (g, v) = (Rc::clone(&gates), Rc::clone(&values));
+    repl.command(
+        "eval",
+        "eval <wire>\n    evaluate the wire using the current input values",
+        move |args| {
+            let wire: String = arg(args, 0, "wire")?;
+            evaluate_wire(&wire, &v.borrow(), &g, &mut FastSet::default()).map(|v| v.to_string())
+        },
+    );
+
+    let (g, v) = (Rc::clone(&gates), Rc::clone(&values));
+    repl.command(
+        "number",
+        "number <x|y|z>\n    combine all wires with the prefix into a number",
+        move |args| {
+            let prefix: String = arg(args, 0, "x|y|z")?;
+            let values = v.borrow();
+            let mut wires: Vec<WireName> = values
+                .keys()
+                .chain(g.keys())
+                .filter(|wire| wire.starts_with(prefix.as_str()))
+                .cloned()
+                .collect();
+            wires.sort();
+            wires.dedup();
+
+            let bits = wires
+                .into_iter()
+                .map(|wire| {
+                    Ok((
+                        wire,
+                        evaluate_wire(wire, &values, &g, &mut FastSet::default())?,
+                    ))
+                })
+                .collect::<Result<Vec<_>, String>>()?;
+            Ok(as_number(&bits).to_string())
+        },
+    );
+
+    let g = Rc::clone(&gates);
+    repl.command(
+        "gate",
+        "gate <wire>\n    show the gate that outputs the wire",
+        move |args| {
+            let wire: String = arg(args, 0, "wire")?;
+            let gate = g
+                .get(wire.as_str())
+                .ok_or(format!("No gate outputs {}", wire))?;
+            let wires = gate.get_undetermined();
+            Ok(format!(
+                "{} {:?} {} -> {}",
+                wires[0], gate.op, wires[1], gate.gout
+            ))
+        },
+    );
+
+    repl.command(
+        "set",
+        "set <wire> <0|1>\n    change an input value",
+        move |args| {
+            let wire: String = arg(args, 0, "wire")?;
+            let value: u8 = arg(args, 1, "0|1")?;
+            let mut values = values.borrow_mut();
+            match values.get_mut(wire.as_str()) {
+                Some(v) if value <= 1 => *v = value,
+                Some(_) => return Err(format!("Not a bit: {}", value)),
+                None => return Err(format!("Not an input wire: {}", wire)),
+            }
+
+            Ok(String::new())
+        },
+    );
+}
+
+fn evaluate_wire(
+    wire: &str,
+    values: &FastMap<WireName, u8>,
+    gates: &FastMap<WireName, Gate>,
+    visiting: &mut FastSet<WireName>,
+) -> Result<u8, String> {
+    if let Some(value) = values.get(wire) {
+        return Ok(*value);
+    }
+
+    let gate = gates.get(wire).ok_or(format!("Unknown wire: {}", wire))?;
+    if !visiting.insert(gate.gout) {
+        return Err(format!("Wire {} depends on itself", wire));
+    }
+
+    let wires = gate.get_undetermined();
+    let v0 = evaluate_wire(wires[0], values, gates, visiting)?;
+    let v1 = evaluate_wire(wires[1], values, gates, visiting)?;
+    visiting.remove(gate.gout);
+
+    Ok(gate.op.run(v0, v1))
+}
+
+/// Validate
+///
+/// Part 2 expects a 45 bit ripple-carry adder, i.e. inputs `x00..x44` and `y00..y44`, outputs
+/// `z00..z45`, and handles `z00` and `z45` as hardcoded edge cases.
+pub fn validate(input: &str, checks: &mut Checks) {
+    let (init, gates) = input.trim().split_once("\n\n").unwrap_or((input, ""));
+    let wires: FastSet<&str> = init
+        .lines()
+        .filter_map(|line| line.split_once(':'))
+        .map(|(wire, _)| wire.trim())
+        .collect();
+    let outputs: Vec<&str> = gates
+        .lines()
+        .filter_map(|line| line.trim().split(" -> ").nth(1))
+        .collect();
+
+    for prefix in ['x', 'y'] {
+        let expected: FastSet<String> = (0..BITS).map(|i| format!("{}{:02}", prefix, i)).collect();
+        let found = wires.iter().filter(|w| w.starts_with(prefix)).count();
+        checks.check(
+            format!("inputs are {0}00..{0}{1:02}", prefix, BITS - 1),
+            found == BITS && expected.iter().all(|w| wires.contains(w.as_str())),
+            format!("{} {} wires", found, prefix),
+        );
+    }
+
+    let zs = outputs.iter().filter(|w| w.starts_with('z')).count();
+    let has_edges = outputs.contains(&"z00") && outputs.contains(&format!("z{:02}", BITS).as_str());
+    checks.check(
+        format!("outputs are z00..z{:02}", BITS),
+        zs == BITS + 1 && has_edges,
+        format!("{} z wires", zs),
+    );
+
+    let malformed = gates
+        .lines()
+        .filter(|line| {
+            let split: Vec<&str> = line.split_whitespace().collect();
+            split.len() != 5 || !["AND", "OR", "XOR"].contains(&split[1]) || split[3] != "->"
+        })
+        .count();
+    checks.check(
+        "every gate is `a OP b -> c` with OP in AND, OR, XOR",
+        malformed == 0,
+        format!("{} malformed gates", malformed),
+    );
+    checks.check(
+        "every wire is driven by at most one gate",
+        outputs.iter().collect::<FastSet<_>>().len() == outputs.len(),
+        format!("{} gates", outputs.len()),
+    );
+}
+
 fn parse_input() -> (Vec<(WireName, u8)>, Vec<Gate>) {
     let mut init_values = Vec::new();
     let mut gates = Vec::new();
@@ -269,6 +497,67 @@ fn parse_input() -> (Vec<(WireName, u8)>, Vec<Gate>) {
     (init_values, gates)
 }
 
+/// Renames the internal wires consistently, randomizes the input bits, swaps the operands of
+/// random gates and shuffles the gates. The `x`, `y` and `z` wires keep their names, as their
+/// names carry the bit positions.
+pub fn anonymize(input: &str, rng: &mut Rng) -> String {
+    let (init, gates) = input.trim().split_once("\n\n").unwrap_or((input, ""));
+    let init: String = init
+        .lines()
+        .filter_map(|line| line.split_once(':'))
+        .map(|(wire, _)| format!("{}: {}\n", wire.trim(), rng.index(2)))
+        .collect();
+
+    let mut names = FastMap::default();
+    let mut gates: Vec<String> = gates
+        .lines()
+        .map(|line| line.split_whitespace().collect::<Vec<_>>())
+        .filter(|split| split.len() == 5)
+        .map(|split| {
+            let mut gin = [
+                anonymous_wire(split[0], &mut names, rng),
+                anonymous_wire(split[2], &mut names, rng),
+            ];
+            if rng.chance(1, 2) {
+                gin.swap(0, 1);
+            }
+            let gout = anonymous_wire(split[4], &mut names, rng);
+            format!("{} {} {} -> {}\n", gin[0], split[1], gin[1], gout)
+        })
+        .collect();
+    rng.shuffle(&mut gates);
+
+    format!("{}\n{}", init, gates.concat())
+}
+
+fn anonymous_wire<'a>(
+    wire: &'a str,
+    names: &mut FastMap<&'a str, String>,
+    rng: &mut Rng,
+) -> String {
+    if wire.starts_with(['x', 'y', 'z']) {
+        return wire.to_string();
+    }
+
+    if let Some(name) = names.get(wire) {
+        return name.clone();
+    }
+
+    let name = loop {
+        // first letter before `x`, so the name can not clash with the input and output wires
+        let name: String = [rng.range(0, 22), rng.range(0, 25), rng.range(0, 25)]
+            .iter()
+            .map(|&c| (b'a' + c as u8) as char)
+            .collect();
+        if !names.values().any(|taken| *taken == name) {
+            break name;
+        }
+    };
+    names.insert(wire, name.clone());
+
+    name
+}
+
 #[derive(Clone, Debug, PartialEq, Eq)]
 struct Gate {
     gin: (Wire, Wire),
@@ -1,3 +1,4 @@
+use crate::etc::bench::Bench;
 use crate::{read_input, Solution, SolutionPair};
 
 type KeyLock = [u8; 5];
@@ -11,6 +12,13 @@ pub fn solve() -> SolutionPair {
     (Solution::from(sol1), Solution::from(sol2))
 }
 
+/// Day 25 has no second part.
+pub fn bench(bench: &mut Bench) {
+    bench.run("parse", parse_input);
+    let (locks, keys) = parse_input();
+    bench.run("part1", || part1(&locks, &keys));
+}
+
 fn part1(locks: &[KeyLock], keys: &[KeyLock]) -> usize {
     keys.iter()
         .map(|key| {
@@ -0,0 +1,94 @@
+use std::str::FromStr;
+
+use hashbrown::HashMap;
+
+/// Args
+///
+/// Minimal command-line parser. Options listed in `with_value` consume the next argument
+/// (or the part after `=`), every other `-x`/`--name` is a boolean flag and the rest are
+/// positional arguments.
+pub struct Args {
+    pub positional: Vec<String>,
+    options: HashMap<String, Vec<String>>,
+}
+
+impl Args {
+    pub fn parse(args: &[String], with_value: &[&str]) -> Self {
+        let mut positional = Vec::new();
+        let mut options: HashMap<String, Vec<String>> = HashMap::new();
+
+        let mut args = args.iter();
+        while let Some(arg) = args.next() {
+            if !arg.starts_with('-') {
+                positional.push(arg.clone());
+                continue;
+            }
+
+            let (name, value) = match arg.split_once('=') {
+                Some((name, value)) => (name, Some(value.to_string())),
+                None => (arg.as_str(), None),
+            };
+
+            let value = if with_value.contains(&name) {
+                Some(value.unwrap_or_else(|| {
+                    args.next()
+                        .unwrap_or_else(|| panic!("Missing value for {}", name))
+                        .clone()
+                }))
+            } else {
+                value
+            };
+
+            let values = options.entry(name.to_string()).or_default();
+            if let Some(value) = value {
+                values.push(value);
+            }
+        }
+
+        Self {
+            positional,
+            options,
+        }
+    }
+
+    pub fn flag(&self, name: &str) -> bool {
+        self.options.contains_key(name)
+    }
+
+    pub fn value<T: FromStr>(&self, name: &str) -> Option<T> {
+        self.options
+            .get(name)
+            .and_then(|values| values.last())
+            .map(|value| {
+                value
+                    .parse()
+                    .unwrap_or_else(|_| panic!("Not a valid value for {}: {}", name, value))
+            })
+    }
+
+    pub fn values<T: FromStr>(&self, name: &str) -> Vec<T> {
+        self.options
+            .get(name)
+            .map(|values| {
+                values
+                    .iter()
+                    .map(|value| {
+                        value
+                            .parse()
+                            .unwrap_or_else(|_| panic!("Not a valid value for {}: {}", name, value))
+                    })
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    pub fn days(&self) -> Vec<u8> {
+        self.positional
+            .iter()
+            .map(|x| {
+                x.parse()
+                    .unwrap_or_else(|v| panic!("Not a valid day: {}", v))
+            })
+            .collect()
+    }
+}
@@ -0,0 +1,287 @@
+use std::fs;
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+use hashbrown::HashMap;
+
+use crate::utils::file::read_file;
+
+pub const BASELINE_DIR: &str = "target/bench";
+
+/// Changes within this fraction of the baseline are reported as noise.
+const NOISE: f64 = 0.05;
+/// Batched iterations of fast functions should take at least this long, so the timer overhead
+/// does not dominate.
+const MIN_SAMPLE: Duration = Duration::from_micros(10);
+
+/// Measurement
+///
+/// Statistics of one benchmarked group (`parse`, `part1`, ...) of a day, all times per iteration.
+#[derive(Clone, Debug)]
+pub struct Measurement {
+    pub day: u8,
+    pub group: String,
+    pub iterations: usize,
+    pub median_ns: f64,
+    pub mean_ns: f64,
+    pub min_ns: f64,
+    pub stddev_ns: f64,
+}
+
+impl Measurement {
+    fn from_samples(day: u8, group: &str, mut samples: Vec<f64>, iterations: usize) -> Self {
+        samples.sort_by(|a, b| a.total_cmp(b));
+        let n = samples.len() as f64;
+        let mean_ns = samples.iter().sum::<f64>() / n;
+        let variance = samples.iter().map(|s| (s - mean_ns).powi(2)).sum::<f64>() / n;
+
+        Self {
+            day,
+            group: group.to_string(),
+            iterations,
+            median_ns: samples[samples.len() / 2],
+            mean_ns,
+            min_ns: samples[0],
+            stddev_ns: variance.sqrt(),
+        }
+    }
+
+    fn to_line(&self) -> String {
+        format!(
+            "{}\t{}\t{}\t{:.1}\t{:.1}\t{:.1}\t{:.1}",
+            self.day,
+            self.group,
+            self.iterations,
+            self.median_ns,
+            self.mean_ns,
+            self.min_ns,
+            self.stddev_ns
+        )
+    }
+
+    fn from_line(line: &str) -> Option<Self> {
+        let mut split = line.split('\t');
+        Some(Self {
+            day: split.next()?.parse().ok()?,
+            group: split.next()?.to_string(),
+            iterations: split.next()?.parse().ok()?,
+            median_ns: split.next()?.parse().ok()?,
+            mean_ns: split.next()?.parse().ok()?,
+            min_ns: split.next()?.parse().ok()?,
+            stddev_ns: split.next()?.parse().ok()?,
+        })
+    }
+}
+
+/// Bench
+///
+/// Collects the measurements of a day. Every group is warmed up and then run repeatedly until
+/// the measurement time is used up.
+pub struct Bench {
+    day: u8,
+    warm_up: Duration,
+    measure: Duration,
+    pub measurements: Vec<Measurement>,
+}
+
+impl Bench {
+    pub fn new(day: u8, measure: Duration) -> Self {
+        Self {
+            day,
+            warm_up: measure / 5,
+            measure,
+            measurements: Vec::new(),
+        }
+    }
+
+    /// Benchmarks `f` as the group `name`.
+    pub fn run<T>(&mut self, name: &str, mut f: impl FnMut() -> T) {
+        // estimate the time of a single iteration to pick the batch size
+        let start = Instant::now();
+        let mut runs = 0;
+        while runs == 0 || start.elapsed() < self.warm_up {
+            black_box(f());
+            runs += 1;
+        }
+        let estimate = start.elapsed() / runs;
+        let batch = (MIN_SAMPLE.as_nanos() / estimate.as_nanos().max(1)).max(1) as u32;
+
+        let mut samples = Vec::new();
+        let start = Instant::now();
+        while samples.is_empty() || start.elapsed() < self.measure {
+            let time = Instant::now();
+            for _ in 0..batch {
+                black_box(f());
+            }
+            samples.push(time.elapsed().as_nanos() as f64 / batch as f64);
+        }
+
+        let iterations = samples.len() * batch as usize;
+        self.measurements.push(Measurement::from_samples(
+            self.day, name, samples, iterations,
+        ));
+    }
+
+    /// Benchmarks `f` as the group `name`, running it on fresh input from `setup` every
+    /// iteration. Only `f` is measured, so parts that consume or modify their input can be
+    /// benchmarked as well.
+    pub fn run_with<I, T>(
+        &mut self,
+        name: &str,
+        mut setup: impl FnMut() -> I,
+        mut f: impl FnMut(I) -> T,
+    ) {
+        let start = Instant::now();
+        while start.elapsed() < self.warm_up {
+            black_box(f(setup()));
+        }
+
+        let mut samples = Vec::new();
+        let mut measured = Duration::ZERO;
+        while samples.is_empty() || measured < self.measure {
+            let input = setup();
+            let time = Instant::now();
+            black_box(f(input));
+            let elapsed = time.elapsed();
+
+            measured += elapsed;
+            samples.push(elapsed.as_nanos() as f64);
+        }
+
+        let iterations = samples.len();
+        self.measurements.push(Measurement::from_samples(
+            self.day, name, samples, iterations,
+        ));
+    }
+}
+
+/// Baseline
+///
+/// Measurements saved under a name in `target/bench/<name>.tsv`.
+pub struct Baseline {
+    measurements: HashMap<(u8, String), Measurement>,
+}
+
+impl Baseline {
+    /// Loads the baseline, an unknown baseline is empty.
+    pub fn load(name: &str) -> Self {
+        let measurements = read_file(&baseline_file(name))
+            .unwrap_or_default()
+            .lines()
+            .filter_map(Measurement::from_line)
+            .map(|m| ((m.day, m.group.clone()), m))
+            .collect();
+
+        Self { measurements }
+    }
+
+    /// Adds the measurements to the baseline and saves it, replacing older measurements of the
+    /// same groups.
+    pub fn save(name: &str, measurements: &[Measurement]) -> std::io::Result<()> {
+        let mut baseline = Self::load(name);
+        for m in measurements {
+            baseline
+                .measurements
+                .insert((m.day, m.group.clone()), m.clone());
+        }
+
+        let mut all: Vec<&Measurement> = baseline.measurements.values().collect();
+        all.sort_by(|a, b| (a.day, &a.group).cmp(&(b.day, &b.group)));
+        let content: String = all.iter().map(|m| m.to_line() + "\n").collect();
+
+        fs::create_dir_all(BASELINE_DIR)?;
+        fs::write(baseline_file(name), content)
+    }
+
+    pub fn get(&self, day: u8, group: &str) -> Option<&Measurement> {
+        self.measurements.get(&(day, group.to_string()))
+    }
+}
+
+/// Human readable comparison of the median time against the baseline.
+pub fn compare(measurement: &Measurement, baseline: Option<&Measurement>) -> String {
+    let Some(baseline) = baseline else {
+        return String::from("no baseline");
+    };
+
+    let change = measurement.median_ns / baseline.median_ns - 1.0;
+    let verdict = if change.abs() <= NOISE {
+        "no change"
+    } else if change < 0.0 {
+        "improved"
+    } else {
+        "regressed"
+    };
+
+    format!("{:+.1}% {}", 100.0 * change, verdict)
+}
+
+pub fn format_ns(ns: f64) -> String {
+    match ns {
+        ns if ns < 1e3 => format!("{:.1} ns", ns),
+        ns if ns < 1e6 => format!("{:.2} µs", ns / 1e3),
+        ns if ns < 1e9 => format!("{:.2} ms", ns / 1e6),
+        ns => format!("{:.2} s", ns / 1e9),
+    }
+}
+
+/// Benchmark hook of the day, `None` if the day has none.
+pub fn get_day_bench(day: u8) -> Option<fn(&mut Bench)> {
+    match day {
+        #[cfg(feature = "day01")]
+        1 => Some(crate::days::day01::bench),
+        #[cfg(feature = "day02")]
+        2 => Some(crate::days::day02::bench),
+        #[cfg(feature = "day03")]
+        3 => Some(crate::days::day03::bench),
+        #[cfg(feature = "day04")]
+        4 => Some(crate::days::day04::bench),
+        #[cfg(feature = "day05")]
+        5 => Some(crate::days::day05::bench),
+        #[cfg(feature = "day06")]
+        6 => Some(crate::days::day06::bench),
+        #[cfg(feature = "day07")]
+        7 => Some(crate::days::day07::bench),
+        #[cfg(feature = "day08")]
+        8 => Some(crate::days::day08::bench),
+        #[cfg(feature = "day09")]
+        9 => Some(crate::days::day09::bench),
+        #[cfg(feature = "day10")]
+        10 => Some(crate::days::day10::bench),
+        #[cfg(feature = "day11")]
+        11 => Some(crate::days::day11::bench),
+        #[cfg(feature = "day12")]
+        12 => Some(crate::days::day12::bench),
+        #[cfg(feature = "day13")]
+        13 => Some(crate::days::day13::bench),
+        #[cfg(feature = "day14")]
+        14 => Some(crate::days::day14::bench),
+        #[cfg(feature = "day15")]
+        15 => Some(crate::days::day15::bench),
+        #[cfg(feature = "day16")]
+        16 => Some(crate::days::day16::bench),
+        #[cfg(feature = "day17")]
+        17 => Some(crate::days::day17::bench),
+        #[cfg(feature = "day18")]
+        18 => Some(crate::days::day18::bench),
+        #[cfg(feature = "day19")]
+        19 => Some(crate::days::day19::bench),
+        #[cfg(feature = "day20")]
+        20 => Some(crate::days::day20::bench),
+        #[cfg(feature = "day21")]
+        21 => Some(crate::days::day21::bench),
+        #[cfg(feature = "day22")]
+        22 => Some(crate::days::day22::bench),
+        #[cfg(feature = "day23")]
+        23 => Some(crate::days::day23::bench),
+        #[cfg(feature = "day24")]
+        24 => Some(crate::days::day24::bench),
+        #[cfg(feature = "day25")]
+        25 => Some(crate::days::day25::bench),
+        _ => None,
+    }
+}
+
+fn baseline_file(name: &str) -> String {
+    format!("{}/{}.tsv", BASELINE_DIR, name)
+}
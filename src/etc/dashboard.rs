@@ -0,0 +1,196 @@
+use std::io::{BufRead, BufReader, Write};
+use std::net::{TcpListener, TcpStream};
+use std::panic;
+
+use hashbrown::HashMap;
+
+use crate::etc::args::Args;
+use crate::etc::runner::{self, DayResult, Verification};
+
+const DEFAULT_PORT: u16 = 8024;
+const HISTORY_LEN: usize = 30;
+
+/// Serve
+///
+/// `serve [--port N]`
+///
+/// Local dashboard with the latest answers of every day, their verification status, a chart of
+/// the recorded run times and allocation stats. Every day can be re-run from the page.
+pub fn run(args: &[String]) {
+    let args = Args::parse(args, &["--port"]);
+    let port = args.value("--port").unwrap_or(DEFAULT_PORT);
+
+    let listener = TcpListener::bind(("127.0.0.1", port))
+        .unwrap_or_else(|e| panic!("Could not listen on port {}: {}", port, e));
+    println!("Dashboard running on http://127.0.0.1:{}", port);
+
+    for stream in listener.incoming().map_while(Result::ok) {
+        if let Err(e) = handle(stream) {
+            eprintln!("Request failed: {}", e);
+        }
+    }
+}
+
+fn handle(mut stream: TcpStream) -> std::io::Result<()> {
+    let mut reader = BufReader::new(&stream);
+    let mut request = String::new();
+    reader.read_line(&mut request)?;
+
+    // skip the headers, none of the routes need them or a body
+    let mut header = String::new();
+    while reader.read_line(&mut header)? > 2 {
+        header.clear();
+    }
+
+    let mut split = request.split_whitespace();
+    let (method, path) = (split.next().unwrap_or(""), split.next().unwrap_or(""));
+    let day = path
+        .strip_prefix("/run/")
+        .and_then(|day| day.parse::<u8>().ok());
+
+    let response = match (method, path, day) {
+        ("GET", "/", _) => {
+            let page = render_page();
+            format!(
+                "HTTP/1.1 200 OK\r\nContent-Type: text/html; charset=utf-8\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                page.len(),
+                page
+            )
+        }
+        ("POST", _, Some(day @ 1..=25)) => match panic::catch_unwind(|| runner::run_day(day)) {
+            Ok(result) => {
+                println!(
+                    "Day {:02} re-run: {} / {} in {:.4} ms",
+                    day, result.part1, result.part2, result.elapsed_ms
+                );
+                String::from("HTTP/1.1 303 See Other\r\nLocation: /\r\nContent-Length: 0\r\nConnection: close\r\n\r\n")
+            }
+            Err(_) => String::from(
+                "HTTP/1.1 500 Internal Server Error\r\nContent-Length: 12\r\nConnection: close\r\n\r\nSolver panic",
+            ),
+        },
+        _ => String::from(
+            "HTTP/1.1 404 Not Found\r\nContent-Length: 9\r\nConnection: close\r\n\r\nNot Found",
+        ),
+    };
+
+    stream.write_all(response.as_bytes())
+}
+
+fn render_page() -> String {
+    let answers = runner::read_answers();
+    let mut history: HashMap<u8, Vec<DayResult>> = HashMap::new();
+    for result in runner::read_history() {
+        history.entry(result.day).or_default().push(result);
+    }
+
+    let mut rows = String::new();
+    for day in 1..=25 {
+        let results = history.get(&day).map(|r| r.as_slice()).unwrap_or(&[]);
+        rows.push_str(&match results.last() {
+            Some(latest) => {
+                let (status, class) = match runner::verify(latest, &answers) {
+                    Verification::Correct => ("correct", "ok"),
+                    Verification::Wrong => ("wrong", "wrong"),
+                    Verification::Unknown => ("unknown", ""),
+                };
+                format!(
+                    "<tr><td>{:02}</td><td>{}</td><td>{}</td><td class=\"{}\">{}</td><td>{:.4} ms</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td></tr>\n",
+                    day,
+                    escape(&latest.part1),
+                    escape(&latest.part2),
+                    class,
+                    status,
+                    latest.elapsed_ms,
+                    latest.allocations,
+                    format_bytes(latest.bytes),
+                    render_chart(results),
+                    render_button(day)
+                )
+            }
+            None => format!(
+                "<tr><td>{:02}</td><td colspan=\"7\">not run yet</td><td>{}</td></tr>\n",
+                day,
+                render_button(day)
+            ),
+        });
+    }
+
+    format!(
+        r#"<!DOCTYPE html>
+<html>
+<head>
+<meta charset="utf-8">
+<title>Advent of Code</title>
+<style>
+body {{ font-family: monospace; background: #0f0f23; color: #cccccc; }}
+table {{ border-collapse: collapse; }}
+td, th {{ padding: 4px 12px; border-bottom: 1px solid #333340; text-align: left; }}
+.ok {{ color: #00cc00; }}
+.wrong {{ color: #ff4040; }}
+button {{ font-family: monospace; }}
+</style>
+</head>
+<body>
+<h1>Advent of Code</h1>
+<table>
+<tr><th>Day</th><th>Part 1</th><th>Part 2</th><th>Status</th><th>Elapsed</th><th>Allocations</th><th>Allocated</th><th>History</th><th></th></tr>
+{}</table>
+</body>
+</html>
+"#,
+        rows
+    )
+}
+
+/// Line chart of the elapsed times of the last runs.
+fn render_chart(results: &[DayResult]) -> String {
+    let results = &results[results.len().saturating_sub(HISTORY_LEN)..];
+    let max = results
+        .iter()
+        .map(|r| r.elapsed_ms)
+        .fold(f64::MIN_POSITIVE, f64::max);
+    let (width, height) = (150.0, 30.0);
+    let step = width / (HISTORY_LEN - 1) as f64;
+
+    let points: Vec<String> = results
+        .iter()
+        .enumerate()
+        .map(|(i, r)| {
+            format!(
+                "{:.1},{:.1}",
+                i as f64 * step,
+                height - r.elapsed_ms / max * (height - 2.0) - 1.0
+            )
+        })
+        .collect();
+
+    format!(
+        "<svg width=\"{}\" height=\"{}\"><title>max {:.4} ms</title><polyline fill=\"none\" stroke=\"#ffff66\" points=\"{}\"/></svg>",
+        width,
+        height,
+        max,
+        points.join(" ")
+    )
+}
+
+fn render_button(day: u8) -> String {
+    format!(
+        "<form method=\"post\" action=\"/run/{}\"><button>Run</button></form>",
+        day
+    )
+}
+
+fn format_bytes(bytes: usize) -> String {
+    match bytes {
+        0..1024 => format!("{} B", bytes),
+        1024..1048576 => format!("{:.1} KiB", bytes as f64 / 1024.0),
+        _ => format!("{:.1} MiB", bytes as f64 / 1048576.0),
+    }
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
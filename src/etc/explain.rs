@@ -0,0 +1,83 @@
+pub type Explainer = fn() -> Explanation;
+
+/// Explanation
+///
+/// The witness behind a day's answers: titled sections of `label: detail` entries, e.g. one
+/// entry per calibration equation with the operators that solve it.
+#[derive(Default, Debug)]
+pub struct Explanation {
+    sections: Vec<Section>,
+}
+
+#[derive(Debug)]
+struct Section {
+    title: String,
+    entries: Vec<(String, String)>,
+}
+
+impl Explanation {
+    /// Starts a new section, following entries are added to it.
+    pub fn section(&mut self, title: impl Into<String>) -> &mut Self {
+        self.sections.push(Section {
+            title: title.into(),
+            entries: Vec::new(),
+        });
+        self
+    }
+
+    /// Adds an entry to the last section.
+    pub fn entry(&mut self, label: impl ToString, detail: impl ToString) -> &mut Self {
+        self.sections
+            .last_mut()
+            .expect("Explanation entries need a section")
+            .entries
+            .push((label.to_string(), detail.to_string()));
+        self
+    }
+
+    pub fn print(&self) {
+        for section in &self.sections {
+            println!("  · {}:", section.title);
+            if section.entries.is_empty() {
+                println!("      (none)");
+            }
+            for (label, detail) in &section.entries {
+                println!("      {}: {}", label, detail);
+            }
+        }
+    }
+}
+
+/// Prints the explanation of the day under its answers.
+pub fn run(day: u8) {
+    match get_day_explainer(day) {
+        Some(explainer) => explainer().print(),
+        None => println!("  · No explanation available for day {:02}", day),
+    }
+}
+
+fn get_day_explainer(day: u8) -> Option<Explainer> {
+    match day {
+        #[cfg(feature = "day01")]
+        1 => Some(crate::days::day01::explain),
+        #[cfg(feature = "day02")]
+        2 => Some(crate::days::day02::explain),
+        #[cfg(feature = "day03")]
+        3 => Some(crate::days::day03::explain),
+        #[cfg(feature = "day04")]
+        4 => Some(crate::days::day04::explain),
+        #[cfg(feature = "day05")]
+        5 => Some(crate::days::day05::explain),
+        #[cfg(feature = "day07")]
+        7 => Some(crate::days::day07::explain),
+        #[cfg(feature = "day13")]
+        13 => Some(crate::days::day13::explain),
+        #[cfg(feature = "day18")]
+        18 => Some(crate::days::day18::explain),
+        #[cfg(feature = "day23")]
+        23 => Some(crate::days::day23::explain),
+        #[cfg(feature = "day24")]
+        24 => Some(crate::days::day24::explain),
+        _ => None,
+    }
+}
@@ -0,0 +1,367 @@
+use std::fs;
+use std::str::FromStr;
+
+use hashbrown::HashMap;
+
+use crate::etc::visualize::{get_day_visualizer, Colour, Frame};
+use crate::utils::file::write_bytes;
+
+const DEFAULT_FPS: u64 = 10;
+const DEFAULT_SCALE: usize = 4;
+
+/// Colours in palette order, the index of a colour is its GIF colour table index.
+const PALETTE: [Colour; 6] = [
+    Colour::Default,
+    Colour::Grey,
+    Colour::Red,
+    Colour::Green,
+    Colour::Yellow,
+    Colour::White,
+];
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Format {
+    Ppm,
+    Pbm,
+    Gif,
+}
+
+impl FromStr for Format {
+    type Err = String;
+
+    fn from_str(format: &str) -> Result<Self, Self::Err> {
+        match format {
+            "ppm" => Ok(Self::Ppm),
+            "pbm" => Ok(Self::Pbm),
+            "gif" => Ok(Self::Gif),
+            _ => Err(format!("Unsupported format: {}", format)),
+        }
+    }
+}
+
+/// Export
+///
+/// Writes the frames of a day to `dir`, either as one PPM/PBM image per frame
+/// (`dayXX_NNNNN.ppm`) or as a single animated GIF (`dayXX.gif`). Every cell is drawn as a
+/// `scale x scale` block of its colour.
+pub fn run(
+    day: u8,
+    dir: &str,
+    format: Format,
+    scale: Option<usize>,
+    fps: Option<u64>,
+    limit: Option<usize>,
+) {
+    let frames = match get_day_visualizer(day) {
+        Some(visualizer) => visualizer().take(limit.unwrap_or(usize::MAX)),
+        None => {
+            println!("  · No frames available for day {:02}", day);
+            return;
+        }
+    };
+
+    fs::create_dir_all(dir).unwrap_or_else(|e| panic!("Could not create {}: {}", dir, e));
+    let scale = scale.unwrap_or(DEFAULT_SCALE);
+
+    let mut count = 0;
+    let mut gif = None;
+    for (i, frame) in frames.enumerate() {
+        match format {
+            Format::Ppm => write(
+                &format!("{}/day{:02}_{:05}.ppm", dir, day, i),
+                &ppm(&frame, scale),
+            ),
+            Format::Pbm => write(
+                &format!("{}/day{:02}_{:05}.pbm", dir, day, i),
+                &pbm(&frame, scale),
+            ),
+            Format::Gif => {
+                let delay = 100 / fps.unwrap_or(DEFAULT_FPS).clamp(1, 100) as u16;
+                gif.get_or_insert_with(|| Gif::new(&frame, scale, delay))
+                    .add_frame(&frame);
+            }
+        }
+        count += 1;
+    }
+
+    if let Some(gif) = gif {
+        write(&format!("{}/day{:02}.gif", dir, day), &gif.finish());
+    }
+
+    println!("  · Exported {} frames to {}", count, dir);
+}
+
+fn write(file_name: &str, content: &[u8]) {
+    write_bytes(file_name, content)
+        .unwrap_or_else(|e| panic!("Could not write {}: {}", file_name, e));
+}
+
+fn palette_index(colour: Colour) -> u8 {
+    PALETTE.iter().position(|c| *c == colour).unwrap() as u8
+}
+
+/// Scales the frame to a row-major list of palette indexes.
+fn pixels(frame: &Frame, scale: usize) -> (usize, usize, Vec<u8>) {
+    let height = frame.cells.len() * scale;
+    let width = frame.cells[0].len() * scale;
+    let mut pixels = Vec::with_capacity(width * height);
+    for row in &frame.cells {
+        let line: Vec<u8> = row
+            .iter()
+            .flat_map(|(_, colour)| std::iter::repeat_n(palette_index(*colour), scale))
+            .collect();
+        for _ in 0..scale {
+            pixels.extend_from_slice(&line);
+        }
+    }
+
+    (width, height, pixels)
+}
+
+fn ppm(frame: &Frame, scale: usize) -> Vec<u8> {
+    let (width, height, pixels) = pixels(frame, scale);
+    let mut out = format!("P6\n{} {}\n255\n", width, height).into_bytes();
+    for p in pixels {
+        out.extend_from_slice(&PALETTE[p as usize].rgb());
+    }
+
+    out
+}
+
+/// Black and white image, every cell that is not background is black.
+fn pbm(frame: &Frame, scale: usize) -> Vec<u8> {
+    let (width, height, pixels) = pixels(frame, scale);
+    let background = palette_index(Colour::Grey);
+    let mut out = format!("P4\n{} {}\n", width, height).into_bytes();
+    for row in pixels.chunks(width) {
+        for byte in row.chunks(8) {
+            out.push(
+                byte.iter()
+                    .enumerate()
+                    .filter(|(_, p)| **p != background)
+                    .fold(0u8, |acc, (i, _)| acc | (0x80 >> i)),
+            );
+        }
+    }
+
+    out
+}
+
+/// Gif
+///
+/// Animated GIF89a writer with a fixed global colour table (`PALETTE`) that loops forever.
+struct Gif {
+    out: Vec<u8>,
+    width: usize,
+    height: usize,
+    scale: usize,
+    delay: u16,
+}
+
+impl Gif {
+    const MIN_CODE_SIZE: u8 = 3;
+
+    fn new(first: &Frame, scale: usize, delay: u16) -> Self {
+        let width = first.cells[0].len() * scale;
+        let height = first.cells.len() * scale;
+
+        let mut out = b"GIF89a".to_vec();
+        out.extend_from_slice(&(width as u16).to_le_bytes());
+        out.extend_from_slice(&(height as u16).to_le_bytes());
+        out.extend_from_slice(&[0xF0 | (Self::MIN_CODE_SIZE - 1), 0, 0]);
+        for i in 0..1 << Self::MIN_CODE_SIZE {
+            out.extend_from_slice(&PALETTE.get(i).map(|c| c.rgb()).unwrap_or([0, 0, 0]));
+        }
+
+        // NETSCAPE2.0 application extension, loop forever
+        out.extend_from_slice(&[0x21, 0xFF, 0x0B]);
+        out.extend_from_slice(b"NETSCAPE2.0");
+        out.extend_from_slice(&[0x03, 0x01, 0x00, 0x00, 0x00]);
+
+        Self {
+            out,
+            width,
+            height,
+            scale,
+            delay,
+        }
+    }
+
+    fn add_frame(&mut self, frame: &Frame) {
+        let (width, height, pixels) = pixels(frame, self.scale);
+        assert!(
+            width == self.width && height == self.height,
+            "All frames must have the same dimensions"
+        );
+
+        // graphic control extension with the frame delay
+        self.out.extend_from_slice(&[0x21, 0xF9, 0x04, 0x00]);
+        self.out.extend_from_slice(&self.delay.to_le_bytes());
+        self.out.extend_from_slice(&[0x00, 0x00]);
+
+        // image descriptor
+        self.out.push(0x2C);
+        self.out.extend_from_slice(&[0, 0, 0, 0]);
+        self.out.extend_from_slice(&(width as u16).to_le_bytes());
+        self.out.extend_from_slice(&(height as u16).to_le_bytes());
+        self.out.push(0);
+
+        self.out.push(Self::MIN_CODE_SIZE);
+        for block in lzw_encode(&pixels, Self::MIN_CODE_SIZE).chunks(255) {
+            self.out.push(block.len() as u8);
+            self.out.extend_from_slice(block);
+        }
+        self.out.push(0);
+    }
+
+    fn finish(mut self) -> Vec<u8> {
+        self.out.push(0x3B);
+        self.out
+    }
+}
+
+/// Variable-length LZW as used by GIF: codes start at `min_code_size + 1` bits, grow up to 12
+/// bits and the table is cleared once it is full.
+fn lzw_encode(indexes: &[u8], min_code_size: u8) -> Vec<u8> {
+    let clear = 1u16 << min_code_size;
+    let end = clear + 1;
+
+    let mut out = Vec::new();
+    let (mut acc, mut bits) = (0u32, 0);
+    let mut emit = |code: u16, size: u8, out: &mut Vec<u8>| {
+        acc |= (code as u32) << bits;
+        bits += size;
+        while bits >= 8 {
+            out.push(acc as u8);
+            acc >>= 8;
+            bits -= 8;
+        }
+    };
+
+    let mut table: HashMap<(u16, u8), u16> = HashMap::new();
+    let mut next_code = end + 1;
+    let mut code_size = min_code_size + 1;
+    emit(clear, code_size, &mut out);
+
+    let mut prefix: Option<u16> = None;
+    for &k in indexes {
+        let p = match prefix {
+            Some(p) => p,
+            None => {
+                prefix = Some(k as u16);
+                continue;
+            }
+        };
+
+        if let Some(&code) = table.get(&(p, k)) {
+            prefix = Some(code);
+            continue;
+        }
+
+        emit(p, code_size, &mut out);
+        if next_code == 4096 {
+            emit(clear, code_size, &mut out);
+            table.clear();
+            next_code = end + 1;
+            code_size = min_code_size + 1;
+        } else {
+            table.insert((p, k), next_code);
+            next_code += 1;
+            if next_code > 1 << code_size && code_size < 12 {
+                code_size += 1;
+            }
+        }
+        prefix = Some(k as u16);
+    }
+
+    if let Some(p) = prefix {
+        emit(p, code_size, &mut out);
+    }
+    emit(end, code_size, &mut out);
+    emit(0, 7, &mut out); // flush the remaining bits
+
+    out
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    /// Reference GIF LZW decoder, the table lags one code behind the encoder.
+    fn lzw_decode(data: &[u8], min_code_size: u8) -> Vec<u8> {
+        let clear = 1usize << min_code_size;
+        let end = clear + 1;
+        let reset = || -> Vec<Vec<u8>> {
+            let mut table: Vec<Vec<u8>> = (0..clear).map(|i| vec![i as u8]).collect();
+            table.push(vec![]);
+            table.push(vec![]);
+            table
+        };
+
+        let mut table = reset();
+        let mut code_size = min_code_size + 1;
+        let (mut acc, mut bits, mut pos) = (0u32, 0, 0);
+        let mut prev: Option<usize> = None;
+        let mut out = Vec::new();
+        loop {
+            while bits < code_size {
+                acc |= (data[pos] as u32) << bits;
+                pos += 1;
+                bits += 8;
+            }
+            let code = (acc & ((1 << code_size) - 1)) as usize;
+            acc >>= code_size;
+            bits -= code_size;
+
+            if code == clear {
+                table = reset();
+                code_size = min_code_size + 1;
+                prev = None;
+                continue;
+            } else if code == end {
+                return out;
+            }
+
+            let entry = match prev {
+                None => table[code].clone(),
+                Some(prev) => {
+                    let entry = if code < table.len() {
+                        table[code].clone()
+                    } else {
+                        let mut entry = table[prev].clone();
+                        entry.push(entry[0]);
+                        entry
+                    };
+                    let mut new = table[prev].clone();
+                    new.push(entry[0]);
+                    table.push(new);
+                    entry
+                }
+            };
+
+            if table.len() == 1 << code_size && code_size < 12 {
+                code_size += 1;
+            }
+
+            out.extend_from_slice(&entry);
+            prev = Some(code);
+        }
+    }
+
+    #[test]
+    fn lzw_round_trip_test() {
+        let mut x = 7u32;
+        let noise: Vec<u8> = (0..50_000)
+            .map(|_| {
+                x = x.wrapping_mul(1103515245).wrapping_add(12345);
+                ((x >> 16) % 6) as u8
+            })
+            .collect();
+        let runs: Vec<u8> = (0..50_000).map(|i| ((i / 37) % 6) as u8).collect();
+
+        for pixels in [vec![1], vec![2; 1000], noise, runs] {
+            let encoded = lzw_encode(&pixels, Gif::MIN_CODE_SIZE);
+            assert_eq!(pixels, lzw_decode(&encoded, Gif::MIN_CODE_SIZE));
+        }
+    }
+}
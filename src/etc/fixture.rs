@@ -0,0 +1,78 @@
+use std::fs;
+
+use crate::etc::args::Args;
+use crate::etc::runner;
+use crate::get_day_solver;
+use crate::utils::file::{read_file, read_input, set_input, write_to_file};
+use crate::utils::random::Rng;
+
+pub type Anonymizer = fn(&str, &mut Rng) -> String;
+
+pub const FIXTURES_DIR: &str = "tests/fixtures";
+pub const ANSWERS_FILE: &str = "tests/fixtures/answers.txt";
+
+const DEFAULT_SEED: u64 = 1;
+
+/// Fixture
+///
+/// `fixture <day> [--input FILE] [--seed S]`
+///
+/// Derives a structurally equivalent input from the real one (by default `input/inputXX.txt`),
+/// e.g. by renaming nodes or re-seeding values, so it can be committed. The fixture is written to
+/// `tests/fixtures/dayXX.txt` and its answers are recorded in `tests/fixtures/answers.txt`,
+/// which the fixture tests check the solvers against.
+pub fn run(args: &[String]) {
+    let args = Args::parse(args, &["--input", "--seed"]);
+    let days = args.days();
+    if days.len() != 1 {
+        panic!("Please provide exactly one day to create a fixture for.");
+    }
+
+    let day = days[0];
+    let seed = args.value("--seed").unwrap_or(DEFAULT_SEED);
+    let anonymizer = get_day_anonymizer(day)
+        .unwrap_or_else(|| panic!("No anonymizer available for day {:02}", day));
+    let input = match args.value::<String>("--input") {
+        Some(file_name) => read_file(&file_name),
+        None => read_input("input", day),
+    }
+    .unwrap_or_else(|e| panic!("Could not read the input for day {:02}: {}", day, e));
+
+    let fixture = anonymizer(&input, &mut Rng::new(seed));
+    set_input(day, fixture.clone());
+    let (p1, p2) = get_day_solver(day)();
+
+    let out = format!("{}/day{:02}.txt", FIXTURES_DIR, day);
+    fs::create_dir_all(FIXTURES_DIR)
+        .and_then(|_| write_to_file(&out, &fixture))
+        .unwrap_or_else(|e| panic!("Could not write {}: {}", out, e));
+
+    let mut answers = runner::read_answers_file(ANSWERS_FILE);
+    answers.insert(day, (p1.to_string(), p2.to_string()));
+    let mut lines: Vec<String> = answers
+        .iter()
+        .map(|(day, (p1, p2))| format!("{} {} {}\n", day, p1, p2))
+        .collect();
+    lines.sort_by_key(|line| line.split(' ').next().unwrap().parse::<u8>().unwrap());
+    fs::write(ANSWERS_FILE, lines.concat())
+        .unwrap_or_else(|e| panic!("Could not write {}: {}", ANSWERS_FILE, e));
+
+    println!(
+        "Day {:02} fixture (seed {}) written to {}, answers: {} / {}",
+        day, seed, out, p1, p2
+    );
+}
+
+fn get_day_anonymizer(day: u8) -> Option<Anonymizer> {
+    match day {
+        #[cfg(feature = "day19")]
+        19 => Some(crate::days::day19::anonymize),
+        #[cfg(feature = "day22")]
+        22 => Some(crate::days::day22::anonymize),
+        #[cfg(feature = "day23")]
+        23 => Some(crate::days::day23::anonymize),
+        #[cfg(feature = "day24")]
+        24 => Some(crate::days::day24::anonymize),
+        _ => None,
+    }
+}
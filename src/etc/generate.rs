@@ -0,0 +1,63 @@
+use crate::etc::args::Args;
+use crate::utils::file::write_to_file;
+use crate::utils::random::Rng;
+
+pub type Generator = fn(usize, &mut Rng) -> String;
+
+const DEFAULT_SIZE: usize = 1000;
+const DEFAULT_SEED: u64 = 1;
+
+/// Generate
+///
+/// `generate <day> [--size N] [--seed S] [--out FILE]`
+///
+/// Writes a random input in the day's exact text format. The meaning of `size` is up to the
+/// day, e.g. grid side length for day 06 or number of buyers for day 22.
+pub fn run(args: &[String]) {
+    let args = Args::parse(args, &["--size", "--seed", "--out"]);
+    let days = args.days();
+    if days.len() != 1 {
+        panic!("Please provide exactly one day to generate an input for.");
+    }
+
+    let day = days[0];
+    let size = args.value("--size").unwrap_or(DEFAULT_SIZE);
+    let seed = args.value("--seed").unwrap_or(DEFAULT_SEED);
+    let out = args
+        .value("--out")
+        .unwrap_or_else(|| format!("input/generated{:02}_{}_{}.txt", day, size, seed));
+
+    let generator = get_day_generator(day)
+        .unwrap_or_else(|| panic!("No generator available for day {:02}", day));
+    let input = generator(size, &mut Rng::new(seed));
+
+    write_to_file(&out, &input).unwrap_or_else(|e| panic!("Could not write {}: {}", out, e));
+    println!(
+        "Day {:02} input (size {}, seed {}) written to {}",
+        day, size, seed, out
+    );
+}
+
+fn get_day_generator(day: u8) -> Option<Generator> {
+    match day {
+        #[cfg(feature = "day01")]
+        1 => Some(crate::days::day01::generate),
+        #[cfg(feature = "day02")]
+        2 => Some(crate::days::day02::generate),
+        #[cfg(feature = "day06")]
+        6 => Some(crate::days::day06::generate),
+        #[cfg(feature = "day07")]
+        7 => Some(crate::days::day07::generate),
+        #[cfg(feature = "day09")]
+        9 => Some(crate::days::day09::generate),
+        #[cfg(feature = "day11")]
+        11 => Some(crate::days::day11::generate),
+        #[cfg(feature = "day14")]
+        14 => Some(crate::days::day14::generate),
+        #[cfg(feature = "day22")]
+        22 => Some(crate::days::day22::generate),
+        #[cfg(feature = "day23")]
+        23 => Some(crate::days::day23::generate),
+        _ => None,
+    }
+}
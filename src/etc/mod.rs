@@ -1 +1,14 @@
+pub mod args;
+pub mod bench;
+pub mod dashboard;
+pub mod explain;
+pub mod export;
+pub mod fixture;
+pub mod generate;
+pub mod params;
+pub mod repl;
+pub mod runner;
 pub mod solution;
+pub mod stream;
+pub mod validate;
+pub mod visualize;
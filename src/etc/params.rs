@@ -0,0 +1,96 @@
+use std::str::FromStr;
+use std::sync::{Mutex, OnceLock};
+
+use hashbrown::{HashMap, HashSet};
+
+use crate::utils::file::read_file;
+
+pub const PARAMS_FILE: &str = "input/params.txt";
+
+static OVERRIDES: OnceLock<HashMap<(u8, String), String>> = OnceLock::new();
+static READ: Mutex<Vec<String>> = Mutex::new(Vec::new());
+
+/// Sets the parameter overrides for the given days.
+///
+/// Every `name=value` from the command line applies to all `days` and takes precedence over the
+/// `<day> name=value` lines of the config file (`#` starts a comment). Without an explicit
+/// `config`, `input/params.txt` is used if it exists.
+pub fn init(config: Option<&str>, cli: &[String], days: &[u8]) {
+    let mut overrides = HashMap::new();
+
+    let content = match config {
+        Some(file_name) => read_file(file_name)
+            .unwrap_or_else(|e| panic!("Could not read config file {}: {}", file_name, e)),
+        None => read_file(PARAMS_FILE).unwrap_or_default(),
+    };
+    for line in content.lines() {
+        let line = line.split('#').next().unwrap().trim();
+        if line.is_empty() {
+            continue;
+        }
+
+        let (day, param) = line.split_once(char::is_whitespace).unwrap_or_else(|| {
+            panic!(
+                "Invalid config line `{}`, expected `<day> name=value`",
+                line
+            )
+        });
+        let day = day
+            .parse()
+            .unwrap_or_else(|_| panic!("Invalid day in config line `{}`", line));
+        let (name, value) = split_param(param.trim());
+        overrides.insert((day, name), value);
+    }
+
+    for param in cli {
+        let (name, value) = split_param(param);
+        for &day in days {
+            overrides.insert((day, name.clone()), value.clone());
+        }
+    }
+
+    OVERRIDES
+        .set(overrides)
+        .expect("Parameters were already initialized");
+}
+
+/// Value of the day's parameter, `default` unless it was overridden.
+pub fn get<T: FromStr>(day: u8, name: &str, default: T) -> T {
+    READ.lock().unwrap().push(name.to_string());
+
+    match OVERRIDES
+        .get()
+        .and_then(|o| o.get(&(day, name.to_string())))
+    {
+        Some(value) => value.parse().unwrap_or_else(|_| {
+            panic!(
+                "Invalid value `{}` for parameter `{}` of day {:02}",
+                value, name, day
+            )
+        }),
+        None => default,
+    }
+}
+
+/// Overridden parameters of the given days that were never read, most likely typos.
+pub fn unread(days: &[u8]) -> Vec<String> {
+    let read: HashSet<String> = READ.lock().unwrap().iter().cloned().collect();
+    let mut unread: Vec<String> = OVERRIDES
+        .get()
+        .into_iter()
+        .flat_map(|o| o.keys())
+        .filter(|(day, name)| days.contains(day) && !read.contains(name))
+        .map(|(_, name)| name.clone())
+        .collect::<HashSet<_>>()
+        .into_iter()
+        .collect();
+    unread.sort();
+    unread
+}
+
+fn split_param(param: &str) -> (String, String) {
+    match param.split_once('=') {
+        Some((name, value)) => (name.trim().to_string(), value.trim().to_string()),
+        None => panic!("Invalid parameter `{}`, expected `name=value`", param),
+    }
+}
@@ -0,0 +1,128 @@
+use std::io::{self, BufRead, Write};
+
+use crate::etc::args::Args;
+
+pub type CommandResult = Result<String, String>;
+type Handler = Box<dyn FnMut(&[&str]) -> CommandResult>;
+
+struct Command {
+    name: &'static str,
+    usage: &'static str,
+    handler: Handler,
+}
+
+/// Repl
+///
+/// Line based command interpreter over a day's parsed input. Each line is split on whitespace,
+/// the first word selects the command and the rest are passed to its handler. `help` lists the
+/// registered commands and `quit` exits.
+pub struct Repl {
+    day: u8,
+    commands: Vec<Command>,
+}
+
+impl Repl {
+    pub fn command<F>(&mut self, name: &'static str, usage: &'static str, handler: F) -> &mut Self
+    where
+        F: FnMut(&[&str]) -> CommandResult + 'static,
+    {
+        self.commands.push(Command {
+            name,
+            usage,
+            handler: Box::new(handler),
+        });
+        self
+    }
+
+    fn help(&self) -> String {
+        let mut help = String::from("help\n    list the commands\nquit\n    exit the repl");
+        for command in &self.commands {
+            help.push_str(&format!("\n{}", command.usage));
+        }
+
+        help
+    }
+
+    fn execute(&mut self, line: &str) -> Option<CommandResult> {
+        let words: Vec<&str> = line.split_whitespace().collect();
+        let (&name, args) = words.split_first()?;
+        match name {
+            "help" => Some(Ok(self.help())),
+            _ => Some(
+                match self
+                    .commands
+                    .iter_mut()
+                    .find(|command| command.name == name)
+                {
+                    Some(command) => (command.handler)(args),
+                    None => Err(format!("Unknown command: {} (try `help`)", name)),
+                },
+            ),
+        }
+    }
+
+    fn run(mut self) {
+        let mut stdout = io::stdout();
+        let mut lines = io::stdin().lock().lines();
+        loop {
+            print!("day{:02}> ", self.day);
+            stdout.flush().unwrap();
+
+            let line = match lines.next() {
+                Some(Ok(line)) => line,
+                _ => break,
+            };
+            if matches!(line.trim(), "quit" | "exit") {
+                break;
+            }
+
+            match self.execute(&line) {
+                Some(Ok(output)) if !output.is_empty() => println!("{}", output),
+                Some(Err(error)) => println!("error: {}", error),
+                _ => {}
+            }
+        }
+    }
+}
+
+/// `repl <day>`
+pub fn run(args: &[String]) {
+    let days = Args::parse(args, &[]).days();
+    if days.len() != 1 {
+        panic!("Please provide exactly one day to explore.");
+    }
+
+    let day = days[0];
+    let register = get_day_repl(day)
+        .unwrap_or_else(|| panic!("No repl commands available for day {:02}", day));
+
+    let mut repl = Repl {
+        day,
+        commands: Vec::new(),
+    };
+    register(&mut repl);
+
+    println!("Day {:02} input loaded, type `help` for the commands.", day);
+    repl.run();
+}
+
+/// Parses a single command argument, `name` is used in the error message.
+pub fn arg<T: std::str::FromStr>(args: &[&str], i: usize, name: &str) -> Result<T, String> {
+    let arg = args.get(i).ok_or(format!("Missing argument <{}>", name))?;
+    arg.parse()
+        .map_err(|_| format!("Not a valid <{}>: {}", name, arg))
+}
+
+fn get_day_repl(day: u8) -> Option<fn(&mut Repl)> {
+    match day {
+        #[cfg(feature = "day05")]
+        5 => Some(crate::days::day05::repl),
+        #[cfg(feature = "day17")]
+        17 => Some(crate::days::day17::repl),
+        #[cfg(feature = "day23")]
+        23 => Some(crate::days::day23::repl),
+        #[cfg(feature = "day24")]
+        24 => Some(crate::days::day24::repl),
+        _ => None,
+    }
+}
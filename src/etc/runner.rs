@@ -0,0 +1,126 @@
+use std::time::{Instant, SystemTime, UNIX_EPOCH};
+
+use hashbrown::HashMap;
+
+use crate::get_day_solver;
+use crate::utils::alloc;
+use crate::utils::file::{append, read_file};
+
+pub const HISTORY_FILE: &str = "input/history.tsv";
+pub const ANSWERS_FILE: &str = "input/answers.txt";
+
+/// Day Result
+///
+/// Answers and measurements of a single run of a day.
+#[derive(Clone, Debug)]
+pub struct DayResult {
+    pub day: u8,
+    pub timestamp: u64,
+    pub part1: String,
+    pub part2: String,
+    pub elapsed_ms: f64,
+    pub allocations: usize,
+    pub bytes: usize,
+}
+
+impl DayResult {
+    fn to_line(&self) -> String {
+        format!(
+            "{}\t{}\t{}\t{}\t{:.4}\t{}\t{}",
+            self.timestamp,
+            self.day,
+            self.part1,
+            self.part2,
+            self.elapsed_ms,
+            self.allocations,
+            self.bytes
+        )
+    }
+
+    fn from_line(line: &str) -> Option<Self> {
+        let mut split = line.split('\t');
+        Some(Self {
+            timestamp: split.next()?.parse().ok()?,
+            day: split.next()?.parse().ok()?,
+            part1: split.next()?.to_string(),
+            part2: split.next()?.to_string(),
+            elapsed_ms: split.next()?.parse().ok()?,
+            allocations: split.next()?.parse().ok()?,
+            bytes: split.next()?.parse().ok()?,
+        })
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Verification {
+    Correct,
+    Wrong,
+    Unknown,
+}
+
+/// Solves the day, measuring the elapsed time and allocations, and appends the result to the
+/// history file.
+pub fn run_day(day: u8) -> DayResult {
+    let func = get_day_solver(day);
+
+    let (allocations, bytes) = alloc::snapshot();
+    let time = Instant::now();
+    let (p1, p2) = func();
+    let elapsed_ms = time.elapsed().as_nanos() as f64 / 1_000_000.0;
+    let (allocations_end, bytes_end) = alloc::snapshot();
+
+    let result = DayResult {
+        day,
+        timestamp: SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|t| t.as_secs())
+            .unwrap_or(0),
+        part1: p1.to_string(),
+        part2: p2.to_string(),
+        elapsed_ms,
+        allocations: allocations_end - allocations,
+        bytes: bytes_end - bytes,
+    };
+
+    // the history is best effort, e.g. the input directory might be read-only
+    let _ = append(HISTORY_FILE, &result.to_line());
+
+    result
+}
+
+/// All recorded results, oldest first.
+pub fn read_history() -> Vec<DayResult> {
+    read_file(HISTORY_FILE)
+        .unwrap_or_default()
+        .lines()
+        .filter_map(DayResult::from_line)
+        .collect()
+}
+
+/// Known answers, one `<day> <part1> <part2>` line per day.
+pub fn read_answers() -> HashMap<u8, (String, String)> {
+    read_answers_file(ANSWERS_FILE)
+}
+
+/// Answers in the format of the answers file, empty if the file does not exist.
+pub fn read_answers_file(file_name: &str) -> HashMap<u8, (String, String)> {
+    read_file(file_name)
+        .unwrap_or_default()
+        .lines()
+        .filter_map(|line| {
+            let mut split = line.split_whitespace();
+            Some((
+                split.next()?.parse().ok()?,
+                (split.next()?.to_string(), split.next()?.to_string()),
+            ))
+        })
+        .collect()
+}
+
+pub fn verify(result: &DayResult, answers: &HashMap<u8, (String, String)>) -> Verification {
+    match answers.get(&result.day) {
+        Some((p1, p2)) if *p1 == result.part1 && *p2 == result.part2 => Verification::Correct,
+        Some(_) => Verification::Wrong,
+        None => Verification::Unknown,
+    }
+}
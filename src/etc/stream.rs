@@ -0,0 +1,73 @@
+use std::io;
+use std::time::Instant;
+
+use crate::etc::args::Args;
+use crate::etc::params;
+use crate::utils::alloc;
+use crate::utils::file::LineReader;
+use crate::SolutionPair;
+
+pub type StreamSolver = fn(&mut LineReader) -> Result<SolutionPair, io::Error>;
+
+/// Stream
+///
+/// `stream <day> --input FILE [--param name=value]... [--config FILE]`
+///
+/// Solves the day on a file that is read as it is parsed instead of being loaded first, meant
+/// for generated stress inputs too large to fit into memory as text.
+pub fn run(args: &[String]) {
+    let args = Args::parse(args, &["--input", "--param", "--config"]);
+    let days = args.days();
+    if days.len() != 1 {
+        panic!("Please provide exactly one day to stream.");
+    }
+
+    let day = days[0];
+    params::init(
+        args.value::<String>("--config").as_deref(),
+        &args.values::<String>("--param"),
+        &days,
+    );
+    let solver = get_day_stream_solver(day)
+        .unwrap_or_else(|| panic!("Day {:02} cannot stream its input", day));
+    let file_name: String = args
+        .value("--input")
+        .unwrap_or_else(|| panic!("Please provide the input file with --input FILE"));
+    let mut lines = LineReader::open(&file_name)
+        .unwrap_or_else(|e| panic!("Could not open {}: {}", file_name, e));
+
+    let (allocations, bytes) = alloc::snapshot();
+    let time = Instant::now();
+    let (p1, p2) =
+        solver(&mut lines).unwrap_or_else(|e| panic!("Could not read {}: {}", file_name, e));
+    let elapsed_ms = time.elapsed().as_nanos() as f64 / 1_000_000.0;
+    let (allocations_end, bytes_end) = alloc::snapshot();
+
+    println!("=== Day {:02} ===", day);
+    println!("  · Part 1: {}", p1);
+    println!("  · Part 2: {}", p2);
+    println!("  · Elapsed: {:.4} ms", elapsed_ms);
+    println!(
+        "  · Allocations: {} ({} bytes)",
+        allocations_end - allocations,
+        bytes_end - bytes
+    );
+}
+
+fn get_day_stream_solver(day: u8) -> Option<StreamSolver> {
+    match day {
+        #[cfg(feature = "day01")]
+        1 => Some(crate::days::day01::solve_stream),
+        #[cfg(feature = "day02")]
+        2 => Some(crate::days::day02::solve_stream),
+        #[cfg(feature = "day03")]
+        3 => Some(crate::days::day03::solve_stream),
+        #[cfg(feature = "day07")]
+        7 => Some(crate::days::day07::solve_stream),
+        #[cfg(feature = "day09")]
+        9 => Some(crate::days::day09::solve_stream),
+        #[cfg(feature = "day22")]
+        22 => Some(crate::days::day22::solve_stream),
+        _ => None,
+    }
+}
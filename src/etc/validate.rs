@@ -0,0 +1,135 @@
+use std::process;
+
+use crate::etc::args::Args;
+use crate::etc::params;
+use crate::utils::file::{read_file, read_input};
+
+pub type Validator = fn(&str, &mut Checks);
+
+/// Checks
+///
+/// Collects the assumptions a solver makes about its input and whether they hold.
+#[derive(Default)]
+pub struct Checks {
+    results: Vec<(String, bool, String)>,
+}
+
+impl Checks {
+    /// Records an assumption, `found` describes what the input actually contains.
+    pub fn check(&mut self, assumption: impl Into<String>, holds: bool, found: impl Into<String>) {
+        self.results.push((assumption.into(), holds, found.into()));
+    }
+
+    /// Records whether values up to `max` fit into the 8 bits a `(a << 8) | b` hash reserves for
+    /// `b`. Larger values only cause collisions, so the answers stay correct but get slower.
+    pub fn packed_hash(&mut self, max: usize) {
+        self.check(
+            "values fit into the 8 bits of the packed hash",
+            max < 256,
+            format!("max {}", max),
+        );
+    }
+
+    pub fn all_hold(&self) -> bool {
+        self.results.iter().all(|(_, holds, _)| *holds)
+    }
+}
+
+/// Validate
+///
+/// `validate <day> [--input FILE] [--param name=value]... [--config FILE]`
+///
+/// Checks the input (by default `input/inputXX.txt`) against the assumptions the day's solver
+/// makes and exits with status 1 if any of them is violated.
+pub fn run(args: &[String]) {
+    let args = Args::parse(args, &["--input", "--param", "--config"]);
+    let days = args.days();
+    if days.len() != 1 {
+        panic!("Please provide exactly one day to validate.");
+    }
+
+    let day = days[0];
+    params::init(
+        args.value::<String>("--config").as_deref(),
+        &args.values::<String>("--param"),
+        &days,
+    );
+    let validator = get_day_validator(day)
+        .unwrap_or_else(|| panic!("No validator available for day {:02}", day));
+    let input = match args.value::<String>("--input") {
+        Some(file_name) => read_file(&file_name),
+        None => read_input("input", day),
+    }
+    .unwrap_or_else(|e| panic!("Could not read the input for day {:02}: {}", day, e));
+
+    let mut checks = Checks::default();
+    validator(&input, &mut checks);
+
+    println!("=== Day {:02} ===", day);
+    for (assumption, holds, found) in &checks.results {
+        let mark = if *holds { "ok" } else { "VIOLATED" };
+        println!("  · [{}] {} (found {})", mark, assumption, found);
+    }
+
+    if !checks.all_hold() {
+        process::exit(1);
+    }
+}
+
+/// Dimensions of a character grid, `None` if the rows have different lengths.
+pub fn grid_dim(input: &str) -> Option<(usize, usize)> {
+    let rows: Vec<&str> = input
+        .trim()
+        .lines()
+        .map(|line| line.trim())
+        .filter(|line| !line.is_empty())
+        .collect();
+    let cols = rows.first()?.len();
+    rows.iter()
+        .all(|row| row.len() == cols)
+        .then_some((rows.len(), cols))
+}
+
+/// Number of tiles on the outermost rows and columns of a grid that are not walls (`#`).
+pub fn border_gaps(input: &str) -> usize {
+    let rows: Vec<&[u8]> = input
+        .trim()
+        .lines()
+        .map(|line| line.trim().as_bytes())
+        .filter(|line| !line.is_empty())
+        .collect();
+    let last = rows.len().saturating_sub(1);
+    rows.iter()
+        .enumerate()
+        .map(|(i, row)| {
+            if i == 0 || i == last {
+                row.iter().filter(|&&c| c != b'#').count()
+            } else {
+                [row.first(), row.last()]
+                    .iter()
+                    .filter(|c| **c != Some(&b'#'))
+                    .count()
+            }
+        })
+        .sum()
+}
+
+fn get_day_validator(day: u8) -> Option<Validator> {
+    match day {
+        #[cfg(feature = "day06")]
+        6 => Some(crate::days::day06::validate),
+        #[cfg(feature = "day12")]
+        12 => Some(crate::days::day12::validate),
+        #[cfg(feature = "day14")]
+        14 => Some(crate::days::day14::validate),
+        #[cfg(feature = "day16")]
+        16 => Some(crate::days::day16::validate),
+        #[cfg(feature = "day18")]
+        18 => Some(crate::days::day18::validate),
+        #[cfg(feature = "day20")]
+        20 => Some(crate::days::day20::validate),
+        #[cfg(feature = "day24")]
+        24 => Some(crate::days::day24::validate),
+        _ => None,
+    }
+}
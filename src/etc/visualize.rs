@@ -0,0 +1,202 @@
+use std::io::{self, BufRead, IsTerminal, Write};
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError};
+use std::thread;
+use std::time::Duration;
+
+pub type Frames = Box<dyn Iterator<Item = Frame>>;
+pub type Visualizer = fn() -> Frames;
+
+const DEFAULT_FPS: u64 = 10;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Colour {
+    Default,
+    Grey,
+    Red,
+    Green,
+    Yellow,
+    White,
+}
+
+impl Colour {
+    fn ansi(&self) -> &'static str {
+        match self {
+            Colour::Default => "\x1b[0m",
+            Colour::Grey => "\x1b[90m",
+            Colour::Red => "\x1b[91m",
+            Colour::Green => "\x1b[92m",
+            Colour::Yellow => "\x1b[93m",
+            Colour::White => "\x1b[97m",
+        }
+    }
+
+    pub fn rgb(&self) -> [u8; 3] {
+        match self {
+            Colour::Default => [0, 0, 0],
+            Colour::Grey => [40, 40, 40],
+            Colour::Red => [230, 50, 50],
+            Colour::Green => [60, 200, 80],
+            Colour::Yellow => [240, 200, 40],
+            Colour::White => [230, 230, 230],
+        }
+    }
+}
+
+/// Frame
+///
+/// Snapshot of a grid simulation. Every cell holds the character to draw and its colour.
+#[derive(Clone, Debug)]
+pub struct Frame {
+    pub title: String,
+    pub cells: Vec<Vec<(char, Colour)>>,
+}
+
+impl Frame {
+    /// Creates a `height x width` frame filled with grey `.`.
+    pub fn new(title: String, height: usize, width: usize) -> Self {
+        Self {
+            title,
+            cells: vec![vec![('.', Colour::Grey); width]; height],
+        }
+    }
+
+    /// Sets the cell at row `i` and column `j`, ignoring positions outside the frame.
+    pub fn set(&mut self, i: usize, j: usize, c: char, colour: Colour) {
+        if let Some(cell) = self.cells.get_mut(i).and_then(|row| row.get_mut(j)) {
+            *cell = (c, colour);
+        }
+    }
+
+    pub fn plain(&self) -> String {
+        let mut out = String::with_capacity(self.cells.len() * (self.cells[0].len() + 1));
+        for row in &self.cells {
+            out.extend(row.iter().map(|(c, _)| c));
+            out.push('\n');
+        }
+
+        out
+    }
+
+    pub fn coloured(&self) -> String {
+        let mut out = String::new();
+        for row in &self.cells {
+            let mut colour = Colour::Default;
+            for &(c, cell_colour) in row {
+                if cell_colour != colour {
+                    out.push_str(cell_colour.ansi());
+                    colour = cell_colour;
+                }
+                out.push(c);
+            }
+            out.push_str(Colour::Default.ansi());
+            out.push('\n');
+        }
+
+        out
+    }
+}
+
+/// Visualize
+///
+/// Renders the frames of a day. On a TTY the frames are drawn in place with colours and can be
+/// controlled by typing a command followed by Enter:
+///
+/// - empty line: next frame
+/// - `p`: play/pause
+/// - `+`/`-`: double/halve the speed
+/// - `q`: quit
+///
+/// Otherwise every frame is printed as plain text.
+pub fn run(day: u8, fps: Option<u64>) {
+    let frames = match get_day_visualizer(day) {
+        Some(visualizer) => visualizer(),
+        None => {
+            println!("  · No visualization available for day {:02}", day);
+            return;
+        }
+    };
+
+    if io::stdout().is_terminal() {
+        play(frames, fps.unwrap_or(DEFAULT_FPS))
+    } else {
+        frames.for_each(|frame| println!("{}\n{}", frame.title, frame.plain()));
+    }
+}
+
+fn play(frames: Frames, mut fps: u64) {
+    let commands = spawn_stdin_reader();
+    let mut playing = false;
+    let mut stdout = io::stdout().lock();
+
+    for (n, frame) in frames.enumerate() {
+        write!(
+            stdout,
+            "\x1b[2J\x1b[H{}\n{}frame {} | {} fps | [Enter] step  [p] play/pause  [+/-] speed  [q] quit\n",
+            frame.title,
+            frame.coloured(),
+            n,
+            fps
+        )
+        .unwrap();
+        stdout.flush().unwrap();
+
+        loop {
+            let command = if playing {
+                match commands.recv_timeout(Duration::from_millis(1000 / fps)) {
+                    Ok(command) => command,
+                    Err(RecvTimeoutError::Timeout) => break,
+                    Err(RecvTimeoutError::Disconnected) => return,
+                }
+            } else {
+                match commands.recv() {
+                    Ok(command) => command,
+                    Err(_) => return,
+                }
+            };
+
+            match command.trim() {
+                "" => break,
+                "p" => playing = !playing,
+                "+" => fps = (fps * 2).min(1000),
+                "-" => fps = (fps / 2).max(1),
+                "q" => return,
+                _ => {}
+            }
+
+            if playing {
+                break;
+            }
+        }
+    }
+}
+
+fn spawn_stdin_reader() -> Receiver<String> {
+    let (tx, rx) = mpsc::channel();
+    thread::spawn(move || {
+        for line in io::stdin().lock().lines().map_while(Result::ok) {
+            if tx.send(line).is_err() {
+                break;
+            }
+        }
+    });
+
+    rx
+}
+
+pub fn get_day_visualizer(day: u8) -> Option<Visualizer> {
+    match day {
+        #[cfg(feature = "day04")]
+        4 => Some(crate::days::day04::visualize),
+        #[cfg(feature = "day06")]
+        6 => Some(crate::days::day06::visualize),
+        #[cfg(feature = "day14")]
+        14 => Some(crate::days::day14::visualize),
+        #[cfg(feature = "day15")]
+        15 => Some(crate::days::day15::visualize),
+        #[cfg(feature = "day16")]
+        16 => Some(crate::days::day16::visualize),
+        #[cfg(feature = "day18")]
+        18 => Some(crate::days::day18::visualize),
+        _ => None,
+    }
+}
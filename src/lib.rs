@@ -0,0 +1,66 @@
+// builds with only some of the days leave parts of the tooling unused
+#![cfg_attr(not(feature = "all-days"), allow(dead_code))]
+
+use etc::solution::Solution;
+
+pub mod days;
+pub mod etc;
+pub mod utils;
+
+pub type SolutionPair = (Solution, Solution);
+
+pub fn get_day_solver(day: u8) -> fn() -> SolutionPair {
+    match day {
+        #[cfg(feature = "day01")]
+        1 => days::day01::solve,
+        #[cfg(feature = "day02")]
+        2 => days::day02::solve,
+        #[cfg(feature = "day03")]
+        3 => days::day03::solve,
+        #[cfg(feature = "day04")]
+        4 => days::day04::solve,
+        #[cfg(feature = "day05")]
+        5 => days::day05::solve,
+        #[cfg(feature = "day06")]
+        6 => days::day06::solve,
+        #[cfg(feature = "day07")]
+        7 => days::day07::solve,
+        #[cfg(feature = "day08")]
+        8 => days::day08::solve,
+        #[cfg(feature = "day09")]
+        9 => days::day09::solve,
+        #[cfg(feature = "day10")]
+        10 => days::day10::solve,
+        #[cfg(feature = "day11")]
+        11 => days::day11::solve,
+        #[cfg(feature = "day12")]
+        12 => days::day12::solve,
+        #[cfg(feature = "day13")]
+        13 => days::day13::solve,
+        #[cfg(feature = "day14")]
+        14 => days::day14::solve,
+        #[cfg(feature = "day15")]
+        15 => days::day15::solve,
+        #[cfg(feature = "day16")]
+        16 => days::day16::solve,
+        #[cfg(feature = "day17")]
+        17 => days::day17::solve,
+        #[cfg(feature = "day18")]
+        18 => days::day18::solve,
+        #[cfg(feature = "day19")]
+        19 => days::day19::solve,
+        #[cfg(feature = "day20")]
+        20 => days::day20::solve,
+        #[cfg(feature = "day21")]
+        21 => days::day21::solve,
+        #[cfg(feature = "day22")]
+        22 => days::day22::solve,
+        #[cfg(feature = "day23")]
+        23 => days::day23::solve,
+        #[cfg(feature = "day24")]
+        24 => days::day24::solve,
+        #[cfg(feature = "day25")]
+        25 => days::day25::solve,
+        _ => unimplemented!("Day {:02} is not available", day),
+    }
+}
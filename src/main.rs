@@ -1,17 +1,20 @@
 use std::env;
-use std::time::Instant;
 
-use days::{
-    day01, day02, day03, day04, day05, day06, day07, day08, day09, day10, day11, day12, day13,
-    day14, day15, day16, day17, day18, day19, day20, day21, day22, day23, day24, day25,
+use advent_of_code_template::etc::args::Args;
+use advent_of_code_template::etc::runner::{self, Verification};
+use advent_of_code_template::etc::{
+    dashboard, explain, export, fixture, generate, params, repl, stream, validate, visualize,
 };
-use etc::solution::Solution;
+use advent_of_code_template::utils::alloc::CountingAllocator;
+use advent_of_code_template::utils::log::{self, Level};
 
-mod days;
-mod etc;
-mod utils;
+#[global_allocator]
+static ALLOCATOR: CountingAllocator = CountingAllocator;
 
-pub type SolutionPair = (Solution, Solution);
+/// Options of the default command that take a value.
+const VALUE_OPTIONS: [&str; 8] = [
+    "--fps", "--export", "--format", "--scale", "--frames", "--trace", "--param", "--config",
+];
 
 fn main() {
     let args: Vec<String> = env::args().collect();
@@ -19,61 +22,89 @@ fn main() {
         panic!("Please provide the day(s) to run as a command-line argument.");
     }
 
-    let days: Vec<u8> = args[1..]
-        .iter()
-        .map(|x| {
-            x.parse()
-                .unwrap_or_else(|v| panic!("Not a valid day: {}", v))
-        })
-        .collect();
+    match args[1].as_str() {
+        "generate" => generate::run(&args[2..]),
+        "fixture" => fixture::run(&args[2..]),
+        "repl" => repl::run(&args[2..]),
+        "serve" => dashboard::run(&args[2..]),
+        "stream" => stream::run(&args[2..]),
+        "validate" => validate::run(&args[2..]),
+        _ => run(&args[1..]),
+    }
+}
 
-    let mut runtime = 0.0;
+fn run(args: &[String]) {
+    let args = Args::parse(args, &VALUE_OPTIONS);
+    let traced: Vec<u8> = args.values("--trace");
+    let days = match args.days() {
+        days if days.is_empty() => traced.clone(),
+        days => days,
+    };
 
-    for day in days {
-        let func = get_day_solver(day);
+    let level = if !traced.is_empty() || args.flag("-vvv") {
+        Some(Level::Trace)
+    } else if args.flag("-vv") {
+        Some(Level::Debug)
+    } else if args.flag("-v") {
+        Some(Level::Info)
+    } else {
+        None
+    };
+    log::init(level, &traced);
+    params::init(
+        args.value::<String>("--config").as_deref(),
+        &args.values::<String>("--param"),
+        &days,
+    );
+
+    let answers = runner::read_answers();
+    let mut runtime = 0.0;
 
-        let time = Instant::now();
-        let (p1, p2) = func();
-        let elapsed_ms = time.elapsed().as_nanos() as f64 / 1_000_000.0;
+    for &day in &days {
+        let result = runner::run_day(day);
 
         println!("\n=== Day {:02} ===", day);
-        println!("  · Part 1: {}", p1);
-        println!("  · Part 2: {}", p2);
-        println!("  · Elapsed: {:.4} ms", elapsed_ms);
+        println!("  · Part 1: {}", result.part1);
+        println!("  · Part 2: {}", result.part2);
+        println!("  · Elapsed: {:.4} ms", result.elapsed_ms);
+        println!(
+            "  · Allocations: {} ({} bytes)",
+            result.allocations, result.bytes
+        );
+        match runner::verify(&result, &answers) {
+            Verification::Correct => println!("  · Verified: correct"),
+            Verification::Wrong => println!("  · Verified: WRONG"),
+            Verification::Unknown => {}
+        }
 
-        runtime += elapsed_ms;
+        if args.flag("--explain") {
+            explain::run(day);
+        }
+
+        if args.flag("--visualize") {
+            visualize::run(day, args.value("--fps"));
+        }
+
+        if let Some(dir) = args.value::<String>("--export") {
+            export::run(
+                day,
+                &dir,
+                args.value("--format").unwrap_or(export::Format::Gif),
+                args.value("--scale"),
+                args.value("--fps"),
+                args.value("--frames"),
+            );
+        }
+
+        runtime += result.elapsed_ms;
     }
 
     println!("Total runtime: {:.4} ms", runtime);
-}
 
-fn get_day_solver(day: u8) -> fn() -> SolutionPair {
-    match day {
-        1 => day01::solve,
-        2 => day02::solve,
-        3 => day03::solve,
-        4 => day04::solve,
-        5 => day05::solve,
-        6 => day06::solve,
-        7 => day07::solve,
-        8 => day08::solve,
-        9 => day09::solve,
-        10 => day10::solve,
-        11 => day11::solve,
-        12 => day12::solve,
-        13 => day13::solve,
-        14 => day14::solve,
-        15 => day15::solve,
-        16 => day16::solve,
-        17 => day17::solve,
-        18 => day18::solve,
-        19 => day19::solve,
-        20 => day20::solve,
-        21 => day21::solve,
-        22 => day22::solve,
-        23 => day23::solve,
-        24 => day24::solve,
-        25 => day25::solve,
-        _ => unimplemented!(),
+    for name in params::unread(&days) {
+        eprintln!(
+            "Warning: parameter `{}` is not used by any of the days",
+            name
+        );
     }
 }
@@ -0,0 +1,132 @@
+use std::collections::VecDeque;
+
+/// Aho-Corasick Automaton
+///
+/// Finds the occurrences of several patterns in a single pass over the text. The transitions
+/// are complete, so every byte is one table lookup, and the bytes that appear in no pattern
+/// share one column of the table.
+///
+/// Texts are fed byte by byte through [`step`](Self::step), which also works for sequences that
+/// are not contiguous in memory, like the columns of a grid.
+#[derive(Clone, Debug)]
+pub struct AhoCorasick {
+    // byte -> column of the transition table, 0 for the bytes of no pattern
+    classes: [usize; 256],
+    columns: usize,
+    transitions: Vec<usize>,
+    // patterns ending at a state, including those that end at its suffixes
+    outputs: Vec<Vec<usize>>,
+    lengths: Vec<usize>,
+}
+
+impl AhoCorasick {
+    pub const START: usize = 0;
+
+    pub fn new<P: AsRef<[u8]>>(patterns: &[P]) -> Self {
+        let mut classes = [0; 256];
+        let mut columns = 1;
+        for c in patterns.iter().flat_map(|p| p.as_ref()) {
+            if classes[*c as usize] == 0 {
+                classes[*c as usize] = columns;
+                columns += 1;
+            }
+        }
+
+        // trie of the patterns, 0 is a missing transition until the failure links fill it
+        let mut transitions = vec![0; columns];
+        let mut outputs = vec![Vec::new()];
+        for (id, pattern) in patterns.iter().enumerate() {
+            let mut state = Self::START;
+            for c in pattern.as_ref() {
+                let i = state * columns + classes[*c as usize];
+                if transitions[i] == 0 {
+                    transitions[i] = outputs.len();
+                    transitions.extend(std::iter::repeat_n(0, columns));
+                    outputs.push(Vec::new());
+                }
+                state = transitions[i];
+            }
+            outputs[state].push(id);
+        }
+
+        // breadth-first, a missing transition goes where the failure link's transition goes
+        let mut fail = vec![Self::START; outputs.len()];
+        let mut queue: VecDeque<usize> = (0..columns)
+            .map(|class| transitions[class])
+            .filter(|&next| next != Self::START)
+            .collect();
+        while let Some(state) = queue.pop_front() {
+            let inherited = outputs[fail[state]].clone();
+            outputs[state].extend(inherited);
+            for class in 0..columns {
+                let i = state * columns + class;
+                let fallback = transitions[fail[state] * columns + class];
+                match transitions[i] {
+                    0 => transitions[i] = fallback,
+                    next => {
+                        fail[next] = fallback;
+                        queue.push_back(next);
+                    }
+                }
+            }
+        }
+
+        Self {
+            classes,
+            columns,
+            transitions,
+            outputs,
+            lengths: patterns.iter().map(|p| p.as_ref().len()).collect(),
+        }
+    }
+
+    /// State after reading `c` in `state`.
+    pub fn step(&self, state: usize, c: u8) -> usize {
+        self.transitions[state * self.columns + self.classes[c as usize]]
+    }
+
+    /// Patterns that end with the last byte read to reach `state`.
+    pub fn matches(&self, state: usize) -> &[usize] {
+        &self.outputs[state]
+    }
+
+    pub fn pattern_len(&self, pattern: usize) -> usize {
+        self.lengths[pattern]
+    }
+
+    /// Every occurrence in `text` as pattern and start, ordered by their end.
+    pub fn find_all(&self, text: &[u8]) -> Vec<(usize, usize)> {
+        let mut found = Vec::new();
+        let mut state = Self::START;
+        for (i, c) in text.iter().enumerate() {
+            state = self.step(state, *c);
+            for &pattern in self.matches(state) {
+                found.push((pattern, i + 1 - self.lengths[pattern]));
+            }
+        }
+
+        found
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn aho_corasick_test() {
+        let automaton = AhoCorasick::new(&["he", "she", "his", "hers"]);
+        assert_eq!(
+            vec![(1, 1), (0, 2), (3, 2), (2, 8)],
+            automaton.find_all(b"ushers this")
+        );
+
+        // overlapping occurrences of the same pattern
+        let automaton = AhoCorasick::new(&["aa", "a"]);
+        assert_eq!(
+            vec![(1, 0), (0, 0), (1, 1), (0, 1), (1, 2)],
+            automaton.find_all(b"aaa")
+        );
+        assert!(AhoCorasick::new(&["xy"]).find_all(b"xxzy").is_empty());
+    }
+}
@@ -1,7 +1,24 @@
 use std::{fs, io};
 use std::fs::{File, OpenOptions};
-use std::io::{ErrorKind, Write};
+use std::io::{BufRead, BufReader, ErrorKind, Write};
 use std::path::Path;
+use std::sync::Mutex;
+
+static INPUTS: Mutex<[Option<&'static str>; 26]> = Mutex::new([None; 26]);
+
+/// Replaces the compiled-in input of the day, e.g. to run the solver on a fixture.
+///
+/// The input is leaked, as solvers may keep `&'static str` slices of it.
+#[allow(dead_code)]
+pub fn set_input(day: u8, input: String) {
+    INPUTS.lock().unwrap()[day as usize] = Some(Box::leak(input.into_boxed_str()));
+}
+
+/// The input set with [`set_input`], used by `read_input!` instead of the compiled-in one.
+pub fn input_override(day: &str) -> Option<&'static str> {
+    let day: usize = day.parse().ok()?;
+    INPUTS.lock().unwrap().get(day).copied().flatten()
+}
 
 #[allow(dead_code)]
 pub fn read_file(file_name: &str) -> Result<String, io::Error> {
@@ -14,6 +31,66 @@ pub fn read_input(dir: &str, day: u8) -> Result<String, io::Error> {
     read_file(&file_name)
 }
 
+/// Line Reader
+///
+/// Reads the input one line at a time into a reused buffer, so inputs of hundreds of MB can be
+/// parsed without ever holding their text. Wraps files as well as the compiled-in inputs.
+pub struct LineReader<'a> {
+    reader: Box<dyn BufRead + 'a>,
+    line: String,
+}
+
+impl<'a> LineReader<'a> {
+    pub fn new(reader: impl BufRead + 'a) -> Self {
+        Self {
+            reader: Box::new(reader),
+            line: String::new(),
+        }
+    }
+
+    pub fn open(file_name: &str) -> Result<LineReader<'static>, io::Error> {
+        let file = File::open(file_name)?;
+        Ok(LineReader::new(BufReader::with_capacity(1 << 16, file)))
+    }
+
+    /// Next line without its line ending, `None` at the end of the input.
+    pub fn next_line(&mut self) -> Result<Option<&str>, io::Error> {
+        self.line.clear();
+        if self.reader.read_line(&mut self.line)? == 0 {
+            return Ok(None);
+        }
+
+        Ok(Some(self.line.trim_end_matches(['\n', '\r'])))
+    }
+
+    /// Calls `f` with every line that is not blank, trimmed.
+    pub fn for_each(&mut self, mut f: impl FnMut(&str)) -> Result<(), io::Error> {
+        while let Some(line) = self.next_line()? {
+            let line = line.trim();
+            if !line.is_empty() {
+                f(line);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Calls `f` with the rest of the input in chunks of whatever the buffer holds, for inputs
+    /// that are a single huge line.
+    pub fn for_each_chunk(&mut self, mut f: impl FnMut(&[u8])) -> Result<(), io::Error> {
+        loop {
+            let chunk = self.reader.fill_buf()?;
+            if chunk.is_empty() {
+                return Ok(());
+            }
+
+            f(chunk);
+            let len = chunk.len();
+            self.reader.consume(len);
+        }
+    }
+}
+
 #[allow(dead_code)]
 pub fn write_to_file(file_name: &str, content: &str) -> Result<(), io::Error> {
     let path = Path::new(file_name);
@@ -28,8 +105,24 @@ pub fn write_to_file(file_name: &str, content: &str) -> Result<(), io::Error> {
     write!(output, "{}", content)
 }
 
+#[allow(dead_code)]
+pub fn write_bytes(file_name: &str, content: &[u8]) -> Result<(), io::Error> {
+    let path = Path::new(file_name);
+    if path.exists() {
+        return Err(io::Error::new(
+            ErrorKind::AlreadyExists,
+            "File already exists.",
+        ));
+    }
+
+    File::create(path)?.write_all(content)
+}
+
 #[allow(dead_code)]
 pub fn append(file_name: &str, content: &str) -> Result<(), io::Error> {
-    let mut file = OpenOptions::new().append(true).open(file_name)?;
+    let mut file = OpenOptions::new()
+        .append(true)
+        .create(true)
+        .open(file_name)?;
     writeln!(file, "{}", content)
 }
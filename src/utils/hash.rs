@@ -0,0 +1,74 @@
+#[cfg(feature = "deterministic")]
+use std::hash::{BuildHasherDefault, Hasher};
+
+use hashbrown::{HashMap, HashSet};
+
+/// Hasher used by all solvers.
+///
+/// By default this is hashbrown's randomly seeded hasher. With the `deterministic` feature it is
+/// the unseeded [`FxHasher`], so iteration order (and with it traces and timings) is the same on
+/// every run.
+#[cfg(not(feature = "deterministic"))]
+pub type FastHasher = hashbrown::DefaultHashBuilder;
+#[cfg(feature = "deterministic")]
+pub type FastHasher = BuildHasherDefault<FxHasher>;
+
+pub type FastMap<K, V> = HashMap<K, V, FastHasher>;
+pub type FastSet<T> = HashSet<T, FastHasher>;
+
+/// Fx Hasher
+///
+/// The multiply-rotate hash used by rustc. It is not DoS resistant, but fast and free of any
+/// random state. Works well with the packed `(a << 8) | b` hashes of the grid points.
+#[cfg(feature = "deterministic")]
+#[derive(Default, Clone, Copy)]
+pub struct FxHasher {
+    hash: u64,
+}
+
+#[cfg(feature = "deterministic")]
+impl FxHasher {
+    const SEED: u64 = 0x51_7c_c1_b7_27_22_0a_95;
+
+    #[inline]
+    fn add(&mut self, word: u64) {
+        self.hash = (self.hash.rotate_left(5) ^ word).wrapping_mul(Self::SEED);
+    }
+}
+
+#[cfg(feature = "deterministic")]
+impl Hasher for FxHasher {
+    fn finish(&self) -> u64 {
+        self.hash
+    }
+
+    fn write(&mut self, bytes: &[u8]) {
+        let mut chunks = bytes.chunks_exact(8);
+        for chunk in &mut chunks {
+            self.add(u64::from_le_bytes(chunk.try_into().unwrap()));
+        }
+        for &byte in chunks.remainder() {
+            self.add(byte as u64);
+        }
+    }
+
+    #[inline]
+    fn write_u8(&mut self, i: u8) {
+        self.add(i as u64);
+    }
+
+    #[inline]
+    fn write_u32(&mut self, i: u32) {
+        self.add(i as u64);
+    }
+
+    #[inline]
+    fn write_u64(&mut self, i: u64) {
+        self.add(i);
+    }
+
+    #[inline]
+    fn write_usize(&mut self, i: usize) {
+        self.add(i as u64);
+    }
+}
@@ -0,0 +1,281 @@
+use std::collections::BTreeMap;
+use std::ops::Range;
+
+/// Sorted Set
+///
+/// Ordered values in a sorted `Vec`. Lookups are binary searches, inserts shift the larger
+/// values, which is cheap for the short obstacle lists of a grid row.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct SortedSet<T> {
+    values: Vec<T>,
+}
+
+impl<T: Ord + Copy> SortedSet<T> {
+    pub fn new() -> Self {
+        Self { values: Vec::new() }
+    }
+
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self {
+            values: Vec::with_capacity(capacity),
+        }
+    }
+
+    /// Inserts the value, `false` if it was already present.
+    pub fn insert(&mut self, value: T) -> bool {
+        match self.values.binary_search(&value) {
+            Ok(_) => false,
+            Err(i) => {
+                self.values.insert(i, value);
+                true
+            }
+        }
+    }
+
+    /// Removes the value, `false` if it was not present.
+    pub fn remove(&mut self, value: T) -> bool {
+        match self.values.binary_search(&value) {
+            Ok(i) => {
+                self.values.remove(i);
+                true
+            }
+            Err(_) => false,
+        }
+    }
+
+    pub fn contains(&self, value: T) -> bool {
+        self.values.binary_search(&value).is_ok()
+    }
+
+    /// Largest value smaller than `value`.
+    pub fn predecessor(&self, value: T) -> Option<T> {
+        let i = self.values.partition_point(|v| *v < value);
+        i.checked_sub(1).map(|i| self.values[i])
+    }
+
+    /// Smallest value larger than `value`.
+    pub fn successor(&self, value: T) -> Option<T> {
+        let i = self.values.partition_point(|v| *v <= value);
+        self.values.get(i).copied()
+    }
+
+    pub fn first(&self) -> Option<T> {
+        self.values.first().copied()
+    }
+
+    pub fn last(&self) -> Option<T> {
+        self.values.last().copied()
+    }
+
+    pub fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &T> {
+        self.values.iter()
+    }
+}
+
+impl<T: Ord + Copy> FromIterator<T> for SortedSet<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut values: Vec<T> = iter.into_iter().collect();
+        values.sort();
+        values.dedup();
+        Self { values }
+    }
+}
+
+/// Interval Set
+///
+/// Disjoint half-open intervals `start..end`, kept sorted by their start. Touching or
+/// overlapping intervals are merged on insert.
+///
+/// First-fit queries remember for every size up to where no interval is large enough, so
+/// allocating from the left, as day 09 does, stays linear overall.
+#[derive(Clone, Debug, Default)]
+pub struct IntervalSet {
+    intervals: BTreeMap<usize, usize>,
+    // hints[size]: every interval starting before this is shorter than `size`
+    hints: Vec<usize>,
+}
+
+impl IntervalSet {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds the interval, merging it with the ones it overlaps or touches.
+    pub fn insert(&mut self, range: Range<usize>) {
+        if range.is_empty() {
+            return;
+        }
+
+        let (mut start, mut end) = (range.start, range.end);
+        if let Some((&s, &e)) = self.intervals.range(..=start).next_back() {
+            if e >= start {
+                start = s;
+                end = end.max(e);
+            }
+        }
+        let merged: Vec<(usize, usize)> = self
+            .intervals
+            .range(start..=end)
+            .map(|(&s, &e)| (s, e))
+            .collect();
+        for (s, e) in merged {
+            self.intervals.remove(&s);
+            end = end.max(e);
+        }
+        self.intervals.insert(start, end);
+
+        let len = end - start;
+        for hint in self.hints.iter_mut().take(len + 1) {
+            *hint = (*hint).min(start);
+        }
+    }
+
+    /// Removes the points of `range`, splitting the intervals it cuts through.
+    pub fn remove(&mut self, range: Range<usize>) {
+        if range.is_empty() {
+            return;
+        }
+
+        let from = match self.intervals.range(..range.start).next_back() {
+            Some((&s, &e)) if e > range.start => s,
+            _ => range.start,
+        };
+        let cut: Vec<(usize, usize)> = self
+            .intervals
+            .range(from..range.end)
+            .map(|(&s, &e)| (s, e))
+            .collect();
+        for (s, e) in cut {
+            self.intervals.remove(&s);
+            if s < range.start {
+                self.intervals.insert(s, range.start);
+            }
+            if e > range.end {
+                self.intervals.insert(range.end, e);
+            }
+        }
+    }
+
+    /// The interval containing `point`.
+    pub fn get(&self, point: usize) -> Option<Range<usize>> {
+        self.intervals
+            .range(..=point)
+            .next_back()
+            .filter(|(_, &e)| e > point)
+            .map(|(&s, &e)| s..e)
+    }
+
+    pub fn contains(&self, point: usize) -> bool {
+        self.get(point).is_some()
+    }
+
+    /// Leftmost interval of at least `size` points that starts before `before`.
+    pub fn first_fit(&mut self, size: usize, before: usize) -> Option<Range<usize>> {
+        if self.hints.len() <= size {
+            self.hints.resize(size + 1, 0);
+        }
+
+        let from = self.hints[size];
+        let found = self
+            .intervals
+            .range(from..before.max(from))
+            .find(|(&s, &e)| e - s >= size)
+            .map(|(&s, &e)| s..e);
+
+        self.hints[size] = match &found {
+            Some(range) => range.start,
+            None => before.max(from),
+        };
+        found
+    }
+
+    /// Takes `size` points from the start of the first fit, returning where they start.
+    pub fn allocate(&mut self, size: usize, before: usize) -> Option<usize> {
+        let range = self.first_fit(size, before)?;
+        self.remove(range.start..range.start + size);
+        Some(range.start)
+    }
+
+    /// Number of intervals.
+    pub fn len(&self) -> usize {
+        self.intervals.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.intervals.is_empty()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = Range<usize>> + '_ {
+        self.intervals.iter().map(|(&s, &e)| s..e)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn sorted_set_test() {
+        let set = SortedSet::from_iter([1, 5, 10, 20, 100]);
+        assert_eq!(None, SortedSet::<usize>::new().predecessor(1));
+        assert_eq!(None, set.predecessor(0));
+        assert_eq!(None, set.predecessor(1));
+        assert_eq!(Some(1), set.predecessor(3));
+        assert_eq!(Some(10), set.predecessor(20));
+        assert_eq!(Some(20), set.predecessor(26));
+        assert_eq!(Some(100), set.predecessor(1234));
+        assert_eq!(Some(1), set.successor(0));
+        assert_eq!(Some(100), set.successor(20));
+        assert_eq!(None, set.successor(100));
+
+        let mut set = set;
+        assert!(set.insert(7));
+        assert!(!set.insert(7));
+        assert_eq!(Some(7), set.successor(5));
+        assert!(set.remove(7));
+        assert!(!set.contains(7));
+    }
+
+    #[test]
+    fn interval_set_test() {
+        let mut set = IntervalSet::new();
+        set.insert(0..2);
+        set.insert(5..8);
+        set.insert(2..3);
+        set.insert(10..12);
+        assert_eq!(vec![0..3, 5..8, 10..12], set.iter().collect::<Vec<_>>());
+
+        set.remove(6..11);
+        assert_eq!(vec![0..3, 5..6, 11..12], set.iter().collect::<Vec<_>>());
+        assert_eq!(Some(0..3), set.get(2));
+        assert!(!set.contains(3));
+
+        set.insert(3..5);
+        assert_eq!(vec![0..6, 11..12], set.iter().collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn first_fit_test() {
+        let mut set = IntervalSet::new();
+        set.insert(0..1);
+        set.insert(2..5);
+        set.insert(8..10);
+        assert_eq!(Some(2..5), set.first_fit(2, 20));
+        assert_eq!(Some(2), set.allocate(2, 20));
+        assert_eq!(Some(8), set.allocate(2, 20));
+        assert_eq!(None, set.allocate(2, 20));
+
+        // freed space is found again
+        set.insert(3..5);
+        assert_eq!(Some(3..5), set.first_fit(2, 20));
+        assert_eq!(None, set.first_fit(2, 3));
+    }
+}
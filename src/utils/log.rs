@@ -0,0 +1,89 @@
+use std::fmt::Arguments;
+use std::sync::atomic::{AtomicU32, AtomicU8, Ordering};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Level {
+    Info = 1,
+    Debug = 2,
+    Trace = 3,
+}
+
+/// Highest enabled level, 0 turns logging off.
+static LEVEL: AtomicU8 = AtomicU8::new(0);
+/// Bitmask of days that log, bit `i` is day `i`. 0 means every day.
+static DAYS: AtomicU32 = AtomicU32::new(0);
+
+pub fn init(level: Option<Level>, days: &[u8]) {
+    LEVEL.store(
+        level.map(|level| level as u8).unwrap_or(0),
+        Ordering::Relaxed,
+    );
+    DAYS.store(
+        days.iter().fold(0, |acc, day| acc | (1 << day)),
+        Ordering::Relaxed,
+    );
+}
+
+/// Only a relaxed load, so disabled log statements cost a single branch and never format
+/// their arguments.
+#[inline(always)]
+pub fn enabled(level: Level) -> bool {
+    level as u8 <= LEVEL.load(Ordering::Relaxed)
+}
+
+/// Checks the day filter for a module path like `crate::days::day06`.
+pub fn target_enabled(module: &str) -> bool {
+    let days = DAYS.load(Ordering::Relaxed);
+    days == 0
+        || get_day(module)
+            .map(|day| days & (1 << day) != 0)
+            .unwrap_or(false)
+}
+
+pub fn write(level: Level, module: &str, args: Arguments) {
+    match get_day(module) {
+        Some(day) => eprintln!("[{:?} day{:02}] {}", level, day, args),
+        None => eprintln!("[{:?}] {}", level, args),
+    }
+}
+
+fn get_day(module: &str) -> Option<u8> {
+    module
+        .rsplit("::")
+        .next()?
+        .strip_prefix("day")?
+        .parse()
+        .ok()
+}
+
+#[macro_export]
+macro_rules! log {
+    ($level:expr, $($arg:tt)+) => {{
+        if $crate::utils::log::enabled($level)
+            && $crate::utils::log::target_enabled(module_path!())
+        {
+            $crate::utils::log::write($level, module_path!(), format_args!($($arg)+));
+        }
+    }};
+}
+
+#[macro_export]
+macro_rules! info {
+    ($($arg:tt)+) => {
+        $crate::log!($crate::utils::log::Level::Info, $($arg)+)
+    };
+}
+
+#[macro_export]
+macro_rules! debug {
+    ($($arg:tt)+) => {
+        $crate::log!($crate::utils::log::Level::Debug, $($arg)+)
+    };
+}
+
+#[macro_export]
+macro_rules! trace {
+    ($($arg:tt)+) => {
+        $crate::log!($crate::utils::log::Level::Trace, $($arg)+)
+    };
+}
@@ -4,12 +4,14 @@ macro_rules! read_input {
         read_input!("../../input", $day)
     }};
     ($path:literal, $day:literal) => {{
-        include_str!(
-            concat!(
-                $path,
-                "/input",
-                stringify!($day),
-                ".txt"
+        $crate::utils::file::input_override(stringify!($day)).unwrap_or(
+            include_str!(
+                concat!(
+                    $path,
+                    "/input",
+                    stringify!($day),
+                    ".txt"
+                )
             )
         )
     }};
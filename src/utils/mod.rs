@@ -1,2 +1,8 @@
+pub mod alloc;
+pub mod automaton;
 pub mod file;
+pub mod hash;
+pub mod intervals;
+pub mod log;
 pub mod macros;
+pub mod random;
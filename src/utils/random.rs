@@ -0,0 +1,46 @@
+/// Rng
+///
+/// Small deterministic SplitMix64 generator, so generated inputs are reproducible from a seed
+/// without pulling in an extra dependency.
+pub struct Rng(u64);
+
+impl Rng {
+    pub fn new(seed: u64) -> Self {
+        Self(seed)
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9E3779B97F4A7C15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58476D1CE4E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D049BB133111EB);
+        z ^ (z >> 31)
+    }
+
+    /// Uniform value in `lo..=hi`.
+    pub fn range(&mut self, lo: u64, hi: u64) -> u64 {
+        assert!(lo <= hi, "Empty range {}..={}", lo, hi);
+        match hi - lo {
+            u64::MAX => self.next_u64(),
+            span => lo + self.next_u64() % (span + 1),
+        }
+    }
+
+    /// Uniform index in `0..n`.
+    pub fn index(&mut self, n: usize) -> usize {
+        assert!(n > 0, "Cannot pick an index from an empty range");
+        self.range(0, n as u64 - 1) as usize
+    }
+
+    /// Returns true with probability `num / den`.
+    pub fn chance(&mut self, num: u64, den: u64) -> bool {
+        self.next_u64() % den < num
+    }
+
+    pub fn shuffle<T>(&mut self, vals: &mut [T]) {
+        for i in (1..vals.len()).rev() {
+            let j = self.index(i + 1);
+            vals.swap(i, j);
+        }
+    }
+}
@@ -0,0 +1,29 @@
+#![cfg(feature = "all-days")]
+
+use std::fs;
+
+use advent_of_code_template::etc::runner::read_answers_file;
+use advent_of_code_template::get_day_solver;
+use advent_of_code_template::utils::file::set_input;
+
+const FIXTURES_DIR: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures");
+
+#[test]
+fn fixtures() {
+    let answers = read_answers_file(&format!("{}/answers.txt", FIXTURES_DIR));
+    assert!(!answers.is_empty(), "No fixture answers found");
+
+    let mut days: Vec<u8> = answers.keys().copied().collect();
+    days.sort();
+    for day in days {
+        let file_name = format!("{}/day{:02}.txt", FIXTURES_DIR, day);
+        let input = fs::read_to_string(&file_name)
+            .unwrap_or_else(|e| panic!("Could not read {}: {}", file_name, e));
+        set_input(day, input);
+
+        let (p1, p2) = get_day_solver(day)();
+        let (e1, e2) = &answers[&day];
+        assert_eq!(p1.to_string(), *e1, "Day {:02} part 1", day);
+        assert_eq!(p2.to_string(), *e2, "Day {:02} part 2", day);
+    }
+}